@@ -0,0 +1,127 @@
+use crate::amount::Amount;
+use crate::client::BitGoClient;
+use crate::error::Result;
+use crate::util::redacted;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessToken {
+    pub id: String,
+    pub label: Option<String>,
+    pub user: Option<String>,
+    #[serde(default)]
+    pub scope: Vec<String>,
+    #[serde(default)]
+    pub ip_restrict: Vec<String>,
+    #[serde(default)]
+    pub spending_limits: Vec<SpendingLimit>,
+    pub created: Option<String>,
+    pub expires: Option<String>,
+    pub is_extensible: Option<bool>,
+    /// The bearer token itself. Only returned when the token is created.
+    pub token: Option<String>,
+}
+
+impl std::fmt::Debug for AccessToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AccessToken")
+            .field("id", &self.id)
+            .field("label", &self.label)
+            .field("user", &self.user)
+            .field("scope", &self.scope)
+            .field("ip_restrict", &self.ip_restrict)
+            .field("spending_limits", &self.spending_limits)
+            .field("created", &self.created)
+            .field("expires", &self.expires)
+            .field("is_extensible", &self.is_extensible)
+            .field("token", &redacted(&self.token))
+            .finish()
+    }
+}
+
+/// Limits how much a token may send of `coin`, in base units.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpendingLimit {
+    pub coin: String,
+    #[serde(rename = "txValueLimit", skip_serializing_if = "Option::is_none")]
+    pub tx_value_limit: Option<Amount>,
+}
+
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAccessTokenOptions {
+    pub label: String,
+    /// Scopes such as `wallet_spend_all` or `wallet_view_all`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub scope: Vec<String>,
+    /// IP addresses or CIDR ranges the token may be used from.
+    #[serde(rename = "ip", skip_serializing_if = "Vec::is_empty")]
+    pub ip_restrict: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub spending_limits: Vec<SpendingLimit>,
+    /// Lifetime of the token in seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub otp: Option<String>,
+}
+
+impl std::fmt::Debug for CreateAccessTokenOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CreateAccessTokenOptions")
+            .field("label", &self.label)
+            .field("scope", &self.scope)
+            .field("ip_restrict", &self.ip_restrict)
+            .field("spending_limits", &self.spending_limits)
+            .field("duration", &self.duration)
+            .field("otp", &redacted(&self.otp))
+            .finish()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AccessTokenList {
+    #[serde(default)]
+    access_tokens: Vec<AccessToken>,
+}
+
+#[async_trait]
+pub trait BitGoAccessTokenAPI {
+    async fn list_access_tokens(&self) -> Result<Vec<AccessToken>>;
+    async fn create_access_token(&self, options: &CreateAccessTokenOptions) -> Result<AccessToken>;
+    async fn revoke_access_token(&self, token_id: &str) -> Result<serde_json::Value>;
+}
+
+#[async_trait]
+impl BitGoAccessTokenAPI for BitGoClient {
+    /// This API call lists the access tokens of the user. The tokens themselves
+    /// are not returned.
+    async fn list_access_tokens(&self) -> Result<Vec<AccessToken>> {
+        let request_url = format!("{url}/api/v2/user/accesstoken", url = self.endpoint);
+        let tokens: AccessTokenList =
+            serde_json::from_value(self.get_api(&request_url, &json!({})).await?)?;
+        Ok(tokens.access_tokens)
+    }
+
+    /// This API call creates a long-lived access token. The session must be
+    /// unlocked or an `otp` given.
+    async fn create_access_token(&self, options: &CreateAccessTokenOptions) -> Result<AccessToken> {
+        let request_url = format!("{url}/api/v2/user/accesstoken", url = self.endpoint);
+        let token = self.post_api(&request_url, options).await?;
+        Ok(serde_json::from_value(token)?)
+    }
+
+    async fn revoke_access_token(&self, token_id: &str) -> Result<serde_json::Value> {
+        let request_url = format!(
+            "{url}/api/v2/user/accesstoken/{token_id}",
+            url = self.endpoint,
+            token_id = token_id,
+        );
+        self.delete_api(&request_url, &json!({})).await
+    }
+}
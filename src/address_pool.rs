@@ -0,0 +1,476 @@
+use crate::error::{Error, Result};
+use crate::wallet::{Address, BitGoWalletAPI, CreateAddressOptions};
+use async_trait::async_trait;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// Persistence for the addresses created ahead of time but not yet handed out.
+///
+/// `pop` must remove and return the address in a single step so that two
+/// callers can never be given the same address. Addresses whose forwarder is
+/// still being deployed are kept as pending until `mark_ready` moves them,
+/// again in a single step, into the pool.
+#[async_trait]
+pub trait AddressPoolStore: Send + Sync {
+    async fn push(&self, wallet_id: &str, identifier: &str, address: Address) -> Result<()>;
+    async fn pop(&self, wallet_id: &str, identifier: &str) -> Result<Option<Address>>;
+    async fn len(&self, wallet_id: &str, identifier: &str) -> Result<usize>;
+    async fn push_pending(&self, wallet_id: &str, identifier: &str, address: Address)
+        -> Result<()>;
+    async fn pending(&self, wallet_id: &str, identifier: &str) -> Result<Vec<Address>>;
+    /// Replaces the pending address with the same id by `address` in the pool.
+    /// Returns `false`, leaving the pool as is, if no such address was pending,
+    /// for instance because a concurrent refill already moved it.
+    async fn mark_ready(&self, wallet_id: &str, identifier: &str, address: Address)
+        -> Result<bool>;
+}
+
+#[derive(Debug, Default)]
+struct WalletAddresses {
+    ready: VecDeque<Address>,
+    pending: Vec<Address>,
+}
+
+#[derive(Debug, Default)]
+pub struct MemoryAddressPoolStore {
+    addresses: Mutex<HashMap<(String, String), WalletAddresses>>,
+}
+
+impl MemoryAddressPoolStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_wallet<T>(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        f: impl FnOnce(&mut WalletAddresses) -> T,
+    ) -> T {
+        let mut addresses = self.addresses.lock().unwrap();
+        f(addresses
+            .entry((wallet_id.to_string(), identifier.to_string()))
+            .or_default())
+    }
+}
+
+#[async_trait]
+impl AddressPoolStore for MemoryAddressPoolStore {
+    async fn push(&self, wallet_id: &str, identifier: &str, address: Address) -> Result<()> {
+        self.with_wallet(wallet_id, identifier, |wallet| {
+            wallet.ready.push_back(address)
+        });
+        Ok(())
+    }
+
+    async fn pop(&self, wallet_id: &str, identifier: &str) -> Result<Option<Address>> {
+        Ok(self.with_wallet(wallet_id, identifier, |wallet| wallet.ready.pop_front()))
+    }
+
+    async fn len(&self, wallet_id: &str, identifier: &str) -> Result<usize> {
+        Ok(self.with_wallet(wallet_id, identifier, |wallet| wallet.ready.len()))
+    }
+
+    async fn push_pending(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        address: Address,
+    ) -> Result<()> {
+        self.with_wallet(wallet_id, identifier, |wallet| wallet.pending.push(address));
+        Ok(())
+    }
+
+    async fn pending(&self, wallet_id: &str, identifier: &str) -> Result<Vec<Address>> {
+        Ok(self.with_wallet(wallet_id, identifier, |wallet| wallet.pending.clone()))
+    }
+
+    async fn mark_ready(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        address: Address,
+    ) -> Result<bool> {
+        Ok(self.with_wallet(wallet_id, identifier, |wallet| {
+            let index = wallet
+                .pending
+                .iter()
+                .position(|pending| pending.id == address.id);
+            match index {
+                Some(index) => {
+                    wallet.pending.remove(index);
+                    wallet.ready.push_back(address);
+                    true
+                }
+                None => false,
+            }
+        }))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AddressPoolConfig {
+    /// Number of unassigned addresses to keep per wallet.
+    pub target_size: usize,
+    /// Options used for every address the pool creates. `count` is ignored, the
+    /// pool creates addresses one at a time.
+    pub create_options: CreateAddressOptions,
+    /// How often the forwarder deployment of a new address is checked.
+    pub forwarder_poll_interval: Duration,
+    /// How long a refill waits for a forwarder before moving on. Addresses that
+    /// are still pending are checked again on the next refill.
+    pub forwarder_timeout: Duration,
+}
+
+impl Default for AddressPoolConfig {
+    fn default() -> Self {
+        AddressPoolConfig {
+            target_size: 20,
+            create_options: CreateAddressOptions::default(),
+            forwarder_poll_interval: Duration::from_secs(5),
+            forwarder_timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Keeps a pool of pre-generated receive addresses per wallet so that deposit
+/// requests don't wait on address creation or forwarder deployment.
+pub struct AddressPool<C, S> {
+    client: C,
+    store: S,
+    config: AddressPoolConfig,
+}
+
+impl<C, S> AddressPool<C, S>
+where
+    C: BitGoWalletAPI + Sync + Send + 'static,
+    S: AddressPoolStore + 'static,
+{
+    pub fn new(client: C, store: S, config: AddressPoolConfig) -> Self {
+        AddressPool {
+            client,
+            store,
+            config,
+        }
+    }
+
+    /// Hands out an unassigned address, creating one on the spot if the pool is empty.
+    /// If the forwarder of that new address isn't deployed within `forwarder_timeout`,
+    /// the address is kept as pending for a later refill and
+    /// [`Error::ForwarderPending`] is returned.
+    pub async fn acquire(&self, wallet_id: &str, identifier: &str) -> Result<Address> {
+        if let Some(address) = self.store.pop(wallet_id, identifier).await? {
+            return Ok(address);
+        }
+        log::warn!(
+            "address pool for wallet {} ({}) is empty, creating address inline",
+            wallet_id,
+            identifier
+        );
+        let address = self.create(wallet_id, identifier).await?;
+        let checked = self
+            .wait_for_forwarder(wallet_id, identifier, address.clone())
+            .await;
+        match checked {
+            Ok(checked) if !is_pending(&checked) => Ok(checked),
+            checked => {
+                // Keep the address so that a later refill can still add it to the pool.
+                self.store
+                    .push_pending(wallet_id, identifier, address.clone())
+                    .await?;
+                match checked {
+                    Ok(_) => Err(Error::ForwarderPending {
+                        address: address.address,
+                    }),
+                    Err(err) => Err(err),
+                }
+            }
+        }
+    }
+
+    /// Tops the pool of a wallet up to `target_size`. Returns the number of
+    /// addresses added to the pool. New addresses are stored as pending first,
+    /// so an address whose forwarder isn't deployed yet, or can't be checked, is
+    /// checked again on the next refill instead of being lost.
+    pub async fn refill(&self, wallet_id: &str, identifier: &str) -> Result<usize> {
+        let mut waiting = self.store.pending(wallet_id, identifier).await?;
+        let available = self.store.len(wallet_id, identifier).await?;
+        let missing = self
+            .config
+            .target_size
+            .saturating_sub(available + waiting.len());
+        let mut create_error = None;
+        for _ in 0..missing {
+            match self.create(wallet_id, identifier).await {
+                Ok(address) => {
+                    self.store
+                        .push_pending(wallet_id, identifier, address.clone())
+                        .await?;
+                    waiting.push(address);
+                }
+                Err(err) => {
+                    create_error = Some(err);
+                    break;
+                }
+            }
+        }
+
+        let mut added = 0;
+        for address in waiting {
+            match self
+                .wait_for_forwarder(wallet_id, identifier, address)
+                .await
+            {
+                Ok(address) if !is_pending(&address) => {
+                    if self
+                        .store
+                        .mark_ready(wallet_id, identifier, address)
+                        .await?
+                    {
+                        added += 1;
+                    }
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    log::warn!("failed to check address of wallet {}: {}", wallet_id, err);
+                }
+            }
+        }
+        match create_error {
+            Some(err) => Err(err),
+            None => Ok(added),
+        }
+    }
+
+    /// Refills the pools of `wallets`, given as `(wallet_id, identifier)`, every `interval`.
+    pub fn spawn_refill(
+        self: Arc<Self>,
+        wallets: Vec<(String, String)>,
+        interval: Duration,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                for (wallet_id, identifier) in wallets.iter() {
+                    if let Err(err) = self.refill(wallet_id, identifier).await {
+                        log::error!("failed to refill address pool of {}: {}", wallet_id, err);
+                    }
+                }
+                tokio::time::sleep(interval).await;
+            }
+        })
+    }
+
+    async fn create(&self, wallet_id: &str, identifier: &str) -> Result<Address> {
+        let options = CreateAddressOptions {
+            count: None,
+            ..self.config.create_options.clone()
+        };
+        let address = self
+            .client
+            .create_address(wallet_id, identifier, &options)
+            .await?;
+        Ok(serde_json::from_value(address)?)
+    }
+
+    /// Polls the address until its forwarder is deployed or `forwarder_timeout`
+    /// elapses. UTXO addresses are returned as is.
+    async fn wait_for_forwarder(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        mut address: Address,
+    ) -> Result<Address> {
+        let started = Instant::now();
+        while is_pending(&address) && started.elapsed() < self.config.forwarder_timeout {
+            tokio::time::sleep(self.config.forwarder_poll_interval).await;
+            address = self
+                .client
+                .get_address(wallet_id, identifier, &address.id)
+                .await?;
+        }
+        Ok(address)
+    }
+}
+
+fn is_pending(address: &Address) -> bool {
+    address
+        .coin_specific
+        .as_ref()
+        .and_then(|coin_specific| coin_specific.get("pendingChainInitialization"))
+        .and_then(|pending| pending.as_bool())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockBitGoClient;
+    use serde_json::json;
+
+    fn config() -> AddressPoolConfig {
+        AddressPoolConfig {
+            target_size: 3,
+            forwarder_poll_interval: Duration::from_millis(1),
+            forwarder_timeout: Duration::from_millis(50),
+            ..AddressPoolConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refill_and_acquire() {
+        let mut mock = MockBitGoClient::new();
+        let mut next = 0;
+        mock.expect_create_address()
+            .times(3)
+            .returning(move |_, _, _| {
+                next += 1;
+                Ok(json!({ "id": format!("id{}", next), "address": format!("addr{}", next) }))
+            });
+        let pool = AddressPool::new(mock, MemoryAddressPoolStore::new(), config());
+
+        assert_eq!(pool.refill("wallet", "tbtc").await.unwrap(), 3);
+        assert_eq!(pool.refill("wallet", "tbtc").await.unwrap(), 0);
+        assert_eq!(
+            pool.acquire("wallet", "tbtc").await.unwrap().address,
+            "addr1"
+        );
+        assert_eq!(
+            pool.acquire("wallet", "tbtc").await.unwrap().address,
+            "addr2"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_waits_for_forwarder() {
+        let mut mock = MockBitGoClient::new();
+        mock.expect_create_address().times(1).returning(|_, _, _| {
+            Ok(json!({
+                "id": "id1",
+                "address": "0xabc",
+                "coinSpecific": { "pendingChainInitialization": true },
+            }))
+        });
+        mock.expect_get_address().returning(|_, _, _| {
+            Ok(serde_json::from_value(json!({
+                "id": "id1",
+                "address": "0xabc",
+                "coinSpecific": { "pendingChainInitialization": false },
+            }))
+            .unwrap())
+        });
+        let pool = AddressPool::new(
+            mock,
+            MemoryAddressPoolStore::new(),
+            AddressPoolConfig {
+                target_size: 1,
+                ..config()
+            },
+        );
+
+        assert_eq!(pool.refill("wallet", "teth").await.unwrap(), 1);
+        let address = pool.acquire("wallet", "teth").await.unwrap();
+        assert!(!is_pending(&address));
+    }
+
+    #[tokio::test]
+    async fn test_acquire_reports_pending_forwarder() {
+        let pending = json!({
+            "id": "id1",
+            "address": "0xabc",
+            "coinSpecific": { "pendingChainInitialization": true },
+        });
+        let mut mock = MockBitGoClient::new();
+        let created = pending.clone();
+        mock.expect_create_address()
+            .times(1)
+            .returning(move |_, _, _| Ok(created.clone()));
+        mock.expect_get_address()
+            .returning(move |_, _, _| Ok(serde_json::from_value(pending.clone()).unwrap()));
+        let pool = AddressPool::new(mock, MemoryAddressPoolStore::new(), config());
+
+        let err = pool.acquire("wallet", "teth").await.unwrap_err();
+        assert!(matches!(err, Error::ForwarderPending { address } if address == "0xabc"));
+        assert_eq!(pool.store.pending("wallet", "teth").await.unwrap().len(), 1);
+        assert!(pool.store.pop("wallet", "teth").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unchecked_address_stays_pending() {
+        let mut mock = MockBitGoClient::new();
+        mock.expect_create_address().times(1).returning(|_, _, _| {
+            Ok(json!({
+                "id": "id1",
+                "address": "0xabc",
+                "coinSpecific": { "pendingChainInitialization": true },
+            }))
+        });
+        let mut seq = mockall::Sequence::new();
+        mock.expect_get_address()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _, _| {
+                Err(crate::error::Error::BitgoError {
+                    msg: "unavailable".to_string(),
+                })
+            });
+        mock.expect_get_address()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _, _| {
+                Ok(serde_json::from_value(json!({ "id": "id1", "address": "0xabc" })).unwrap())
+            });
+        let pool = AddressPool::new(
+            mock,
+            MemoryAddressPoolStore::new(),
+            AddressPoolConfig {
+                target_size: 1,
+                ..config()
+            },
+        );
+
+        assert_eq!(pool.refill("wallet", "teth").await.unwrap(), 0);
+        assert_eq!(pool.store.pending("wallet", "teth").await.unwrap().len(), 1);
+        assert_eq!(pool.refill("wallet", "teth").await.unwrap(), 1);
+        assert!(pool
+            .store
+            .pending("wallet", "teth")
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(pool.acquire("wallet", "teth").await.unwrap().id, "id1");
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_refills_add_address_once() {
+        let mut mock = MockBitGoClient::new();
+        mock.expect_create_address().times(0);
+        mock.expect_get_address().returning(|_, _, _| {
+            Ok(serde_json::from_value(json!({ "id": "id1", "address": "0xabc" })).unwrap())
+        });
+        let store = MemoryAddressPoolStore::new();
+        let pending = serde_json::from_value(json!({
+            "id": "id1",
+            "address": "0xabc",
+            "coinSpecific": { "pendingChainInitialization": true },
+        }))
+        .unwrap();
+        store.push_pending("wallet", "teth", pending).await.unwrap();
+        let pool = AddressPool::new(
+            mock,
+            store,
+            AddressPoolConfig {
+                target_size: 1,
+                ..config()
+            },
+        );
+
+        let (first, second) =
+            tokio::join!(pool.refill("wallet", "teth"), pool.refill("wallet", "teth"));
+        assert_eq!(first.unwrap() + second.unwrap(), 1);
+        assert_eq!(pool.store.len("wallet", "teth").await.unwrap(), 1);
+        assert_eq!(pool.acquire("wallet", "teth").await.unwrap().id, "id1");
+        assert!(pool.store.pop("wallet", "teth").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,119 @@
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+const MAX_EXACT_F64: f64 = 9_007_199_254_740_992.0;
+
+/// An exact amount in base units (satoshi, wei, ...), kept as the decimal string
+/// BitGo returns. Amounts of account coins routinely exceed what an `f64` or even
+/// a `u64` can hold, so they are never converted implicitly.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Amount(String);
+
+impl Amount {
+    pub fn new(amount: &str) -> Self {
+        Amount(amount.to_string())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns `None` if the amount does not fit into a `u128` or is not an integer.
+    pub fn to_u128(&self) -> Option<u128> {
+        self.0.parse().ok()
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<u64> for Amount {
+    fn from(amount: u64) -> Self {
+        Amount(amount.to_string())
+    }
+}
+
+impl From<u128> for Amount {
+    fn from(amount: u128) -> Self {
+        Amount(amount.to_string())
+    }
+}
+
+impl Serialize for Amount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Amount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct AmountVisitor;
+
+        impl<'de> Visitor<'de> for AmountVisitor {
+            type Value = Amount;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an integer amount as a string or number")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Amount, E> {
+                let digits = v.strip_prefix('-').unwrap_or(v);
+                if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(E::custom(format!("amount {:?} is not an integer", v)));
+                }
+                Ok(Amount::new(v))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Amount, E> {
+                Ok(Amount::from(v))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Amount, E> {
+                Ok(Amount(v.to_string()))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Amount, E> {
+                if v.fract() != 0.0 {
+                    return Err(E::custom(format!("amount {} is not an integer", v)));
+                }
+                // Above 2^53 a float no longer holds every integer, so the amount
+                // BitGo meant can't be recovered.
+                if v.abs() > MAX_EXACT_F64 {
+                    return Err(E::custom(format!(
+                        "amount {} is too large to be sent as a number",
+                        v
+                    )));
+                }
+                Ok(Amount(format!("{:.0}", v)))
+            }
+        }
+
+        deserializer.deserialize_any(AmountVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_deserialize_amount() {
+        let amount: Amount = serde_json::from_value(json!("123456789012345678901234")).unwrap();
+        assert_eq!(amount.as_str(), "123456789012345678901234");
+        let amount: Amount = serde_json::from_value(json!(-10000)).unwrap();
+        assert_eq!(amount.as_str(), "-10000");
+        let amount: Amount = serde_json::from_value(json!(1e15)).unwrap();
+        assert_eq!(amount.as_str(), "1000000000000000");
+
+        assert!(serde_json::from_value::<Amount>(json!("1.5")).is_err());
+        assert!(serde_json::from_value::<Amount>(json!("")).is_err());
+        assert!(serde_json::from_value::<Amount>(json!("abc")).is_err());
+        assert!(serde_json::from_value::<Amount>(json!(1.5)).is_err());
+        assert!(serde_json::from_value::<Amount>(json!(1e19)).is_err());
+    }
+}
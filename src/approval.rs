@@ -0,0 +1,158 @@
+use crate::client::BitGoClient;
+use crate::error::Result;
+use crate::util::redacted;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingApproval {
+    pub id: String,
+    pub coin: Option<String>,
+    pub wallet: Option<String>,
+    pub enterprise: Option<String>,
+    pub creator: Option<String>,
+    pub create_date: Option<String>,
+    /// `pending`, `approved`, `rejected` or `canceled`.
+    pub state: Option<String>,
+    pub scope: Option<String>,
+    pub approvals_required: Option<i32>,
+    #[serde(default)]
+    pub user_ids: Vec<String>,
+    pub info: Option<PendingApprovalInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingApprovalInfo {
+    /// e.g. `transactionRequest`, `policyRuleRequest` or `updateApprovalsRequiredRequest`.
+    #[serde(rename = "type")]
+    pub approval_type: Option<String>,
+    pub transaction_request: Option<serde_json::Value>,
+    pub policy_rule_request: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingApprovalList {
+    #[serde(default)]
+    pub pending_approvals: Vec<PendingApproval>,
+    pub next_batch_prev_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingApprovalListOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wallet_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enterprise: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev_id: Option<String>,
+}
+
+/// Options for approving or rejecting a pending approval. `wallet_passphrase` is
+/// only needed when approving a transaction that BitGo Express has to co-sign.
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingApprovalUpdateOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub otp: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wallet_passphrase: Option<String>,
+}
+
+impl std::fmt::Debug for PendingApprovalUpdateOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PendingApprovalUpdateOptions")
+            .field("otp", &redacted(&self.otp))
+            .field("wallet_passphrase", &redacted(&self.wallet_passphrase))
+            .finish()
+    }
+}
+
+#[async_trait]
+pub trait BitGoApprovalAPI {
+    async fn list_pending_approvals(
+        &self,
+        options: &PendingApprovalListOptions,
+    ) -> Result<PendingApprovalList>;
+    async fn get_pending_approval(&self, approval_id: &str) -> Result<PendingApproval>;
+    async fn approve_pending_approval(
+        &self,
+        approval_id: &str,
+        identifier: &str,
+        options: &PendingApprovalUpdateOptions,
+    ) -> Result<PendingApproval>;
+    async fn reject_pending_approval(
+        &self,
+        approval_id: &str,
+        identifier: &str,
+        options: &PendingApprovalUpdateOptions,
+    ) -> Result<PendingApproval>;
+}
+
+#[async_trait]
+impl BitGoApprovalAPI for BitGoClient {
+    /// This API call lists the pending approvals of a wallet or enterprise, one page at a time.
+    async fn list_pending_approvals(
+        &self,
+        options: &PendingApprovalListOptions,
+    ) -> Result<PendingApprovalList> {
+        let request_url = format!("{url}/api/v2/pendingapprovals", url = self.endpoint);
+        let approvals = self.get_api(&request_url, options).await?;
+        Ok(serde_json::from_value(approvals)?)
+    }
+
+    async fn get_pending_approval(&self, approval_id: &str) -> Result<PendingApproval> {
+        let request_url = format!(
+            "{url}/api/v2/pendingapprovals/{approval_id}",
+            url = self.endpoint,
+            approval_id = approval_id,
+        );
+        let approval = self.get_api(&request_url, &json!({})).await?;
+        Ok(serde_json::from_value(approval)?)
+    }
+
+    /// This API call approves a pending approval. Approving a transaction goes
+    /// through BitGo Express, which signs it with the wallet passphrase.
+    async fn approve_pending_approval(
+        &self,
+        approval_id: &str,
+        identifier: &str,
+        options: &PendingApprovalUpdateOptions,
+    ) -> Result<PendingApproval> {
+        update_pending_approval(self, approval_id, identifier, "approved", options).await
+    }
+
+    async fn reject_pending_approval(
+        &self,
+        approval_id: &str,
+        identifier: &str,
+        options: &PendingApprovalUpdateOptions,
+    ) -> Result<PendingApproval> {
+        update_pending_approval(self, approval_id, identifier, "rejected", options).await
+    }
+}
+
+async fn update_pending_approval(
+    client: &BitGoClient,
+    approval_id: &str,
+    identifier: &str,
+    state: &str,
+    options: &PendingApprovalUpdateOptions,
+) -> Result<PendingApproval> {
+    let request_url = format!(
+        "{url}/api/v2/{coin_type}/pendingapprovals/{approval_id}",
+        url = client.endpoint,
+        coin_type = identifier,
+        approval_id = approval_id,
+    );
+    let mut body = serde_json::to_value(options)?;
+    body["state"] = json!(state);
+    let approval = client.put_api(&request_url, &body).await?;
+    Ok(serde_json::from_value(approval)?)
+}
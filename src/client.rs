@@ -4,25 +4,59 @@ use crate::config::Config;
 use crate::error::{Error, Result};
 use log::trace;
 use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
-use reqwest::{Certificate, Client, ClientBuilder, RequestBuilder};
+use reqwest::{ClientBuilder, RequestBuilder};
 use std::io::Read;
+use std::sync::{Arc, RwLock};
 
-#[derive(Debug, Clone)]
+/// Clones share the bearer token, so a token set with [`BitGoClient::set_token`]
+/// is used by every clone.
+#[derive(Clone)]
 pub struct BitGoClient {
     pub endpoint: String,
-    pub token: String,
+    token: Arc<RwLock<String>>,
     pub bitgo_cert_path: Option<String>,
 }
 
+impl std::fmt::Debug for BitGoClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BitGoClient")
+            .field("endpoint", &self.endpoint)
+            .field("token", &"<redacted>")
+            .field("bitgo_cert_path", &self.bitgo_cert_path)
+            .finish()
+    }
+}
+
 impl BitGoClient {
     pub fn new(endpoint: String, token: String, bitgo_cert_path: Option<String>) -> Result<Self> {
         Ok(BitGoClient {
             endpoint,
-            token,
+            token: Arc::new(RwLock::new(token)),
             bitgo_cert_path,
         })
     }
 
+    pub fn token(&self) -> String {
+        self.token.read().unwrap().clone()
+    }
+
+    /// Replaces the bearer token used for new requests, e.g. after rotating it.
+    /// Requests already sent keep the token they were sent with. The token is
+    /// replaced for every clone of this client.
+    pub fn set_token(&self, token: String) {
+        *self.token.write().unwrap() = token;
+    }
+
+    /// Returns a client for the same endpoint with its own token, independent of
+    /// this client and its clones.
+    pub fn with_token(&self, token: String) -> Self {
+        BitGoClient {
+            endpoint: self.endpoint.clone(),
+            token: Arc::new(RwLock::new(token)),
+            bitgo_cert_path: self.bitgo_cert_path.clone(),
+        }
+    }
+
     pub async fn get_api<T: serde::Serialize>(
         &self,
         request_url: &str,
@@ -33,15 +67,14 @@ impl BitGoClient {
             Some(path) => path.to_string(),
             None => "".to_string(),
         };
-        let builder;
-        if !cert.is_empty() {
+        let builder = if !cert.is_empty() {
             let client = BitGoClient::get_ssl_certificate(cert)?;
-            builder = ClientBuilder::build(client)?.get(request_url);
+            ClientBuilder::build(client)?.get(request_url)
         } else {
-            builder = reqwest::Client::new().get(request_url);
-        }
+            reqwest::Client::new().get(request_url)
+        };
 
-        self.call_api(builder, params).await
+        self.call_api(builder.query(&query_pairs(params)?)).await
     }
 
     pub async fn post_api<T: serde::Serialize>(
@@ -54,17 +87,16 @@ impl BitGoClient {
             Some(path) => path.to_string(),
             None => "".to_string(),
         };
-        let builder;
-        if !cert.is_empty() {
+        let builder = if !cert.is_empty() {
             let client = BitGoClient::get_ssl_certificate(cert)?;
-            builder = ClientBuilder::build(client)?.post(request_url);
+            ClientBuilder::build(client)?.post(request_url)
         } else {
-            builder = reqwest::Client::new().post(request_url);
-        }
-        self.call_api(builder, params).await
+            reqwest::Client::new().post(request_url)
+        };
+        self.call_api(builder.json(params)).await
     }
 
-    pub async fn delete_api<T: serde::Serialize>(
+    pub async fn put_api<T: serde::Serialize>(
         &self,
         request_url: &str,
         params: &T,
@@ -74,26 +106,39 @@ impl BitGoClient {
             Some(path) => path.to_string(),
             None => "".to_string(),
         };
-        let builder;
-        if !cert.is_empty() {
+        let builder = if !cert.is_empty() {
             let client = BitGoClient::get_ssl_certificate(cert)?;
-            builder = ClientBuilder::build(client)?.delete(request_url);
+            ClientBuilder::build(client)?.put(request_url)
         } else {
-            builder = reqwest::Client::new().delete(request_url);
-        }
-
-        self.call_api(builder, params).await
+            reqwest::Client::new().put(request_url)
+        };
+        self.call_api(builder.json(params)).await
     }
 
-    async fn call_api<T: serde::Serialize>(
+    pub async fn delete_api<T: serde::Serialize>(
         &self,
-        builder: RequestBuilder,
+        request_url: &str,
         params: &T,
     ) -> Result<serde_json::Value> {
+        log::trace!("request url {:?}", request_url);
+        let cert = match self.bitgo_cert_path.as_ref() {
+            Some(path) => path.to_string(),
+            None => "".to_string(),
+        };
+        let builder = if !cert.is_empty() {
+            let client = BitGoClient::get_ssl_certificate(cert)?;
+            ClientBuilder::build(client)?.delete(request_url)
+        } else {
+            reqwest::Client::new().delete(request_url)
+        };
+
+        self.call_api(builder.json(params)).await
+    }
+
+    async fn call_api(&self, builder: RequestBuilder) -> Result<serde_json::Value> {
         let response = builder
             .header(CONTENT_TYPE, "application/json")
-            .header(AUTHORIZATION, format!("Bearer {}", self.token))
-            .json(params)
+            .header(AUTHORIZATION, format!("Bearer {}", self.token()))
             .send()
             .await?;
         if response.status().is_success() {
@@ -106,6 +151,9 @@ impl BitGoClient {
                 Some(value) => value.to_string(),
                 None => "Unknown Error".to_string(),
             };
+            if needs_unlock(&err_json) {
+                return Err(Error::NeedsUnlock { msg });
+            }
             Err(Error::BitgoError { msg })
         }
     }
@@ -127,3 +175,83 @@ impl BitGoClient {
         )
     }
 }
+
+/// BitGo answers operations that need an unlocked session with a 401 carrying
+/// `needsUnlock: true`, or on older endpoints only the `needs unlock` error text.
+fn needs_unlock(err_json: &serde_json::Value) -> bool {
+    err_json.get("needsUnlock").and_then(|v| v.as_bool()) == Some(true)
+        || err_json.get("error").and_then(|v| v.as_str()) == Some("needs unlock")
+}
+
+/// Flattens `params` into query string pairs. Null values are dropped and arrays
+/// are sent as repeated keys (`chains=0&chains=1`), which is what BitGo expects.
+fn query_pairs<T: serde::Serialize>(params: &T) -> Result<Vec<(String, String)>> {
+    fn to_query_value(value: &serde_json::Value) -> String {
+        match value {
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+
+    let mut pairs = Vec::new();
+    if let serde_json::Value::Object(map) = serde_json::to_value(params)? {
+        for (key, value) in map {
+            match value {
+                serde_json::Value::Null => {}
+                serde_json::Value::Array(values) => {
+                    for value in values.iter().filter(|v| !v.is_null()) {
+                        pairs.push((key.clone(), to_query_value(value)));
+                    }
+                }
+                value => pairs.push((key, to_query_value(&value))),
+            }
+        }
+    }
+    Ok(pairs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_query_pairs() {
+        let pairs = query_pairs(&json!({
+            "limit": 10,
+            "prevId": null,
+            "chains": [0, null, 10],
+            "label": "cold",
+        }))
+        .unwrap();
+
+        let pairs: Vec<(&str, &str)> = pairs
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("chains", "0"),
+                ("chains", "10"),
+                ("label", "cold"),
+                ("limit", "10"),
+            ]
+        );
+        assert!(query_pairs(&"".to_string()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_set_token_is_shared_by_clones() {
+        let client =
+            BitGoClient::new("http://localhost".to_string(), "old".to_string(), None).unwrap();
+        let clone = client.clone();
+        client.set_token("new".to_string());
+        assert_eq!(clone.token(), "new");
+        assert!(!format!("{:?}", client).contains("new"));
+
+        let other = client.with_token("other".to_string());
+        client.set_token("newer".to_string());
+        assert_eq!(other.token(), "other");
+    }
+}
@@ -0,0 +1,380 @@
+use crate::error::Result;
+use crate::util::append_line;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::File;
+use std::future::Future;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Body of a notification as delivered by BitGo to a webhook url.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookNotification {
+    #[serde(rename = "type")]
+    pub webhook_type: String,
+    pub coin: Option<String>,
+    pub wallet: Option<String>,
+    pub transfer: Option<String>,
+    /// Transaction hash, or the block hash of `block` notifications.
+    pub hash: Option<String>,
+    /// Set on `pendingapproval` notifications.
+    pub pending_approval_id: Option<String>,
+    /// Set on `address_confirmation` notifications.
+    pub address: Option<String>,
+    pub state: Option<String>,
+    pub confirmations: Option<u32>,
+}
+
+impl WebhookNotification {
+    pub fn from_json(body: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(body)?)
+    }
+
+    /// Identity of the logical event carried by this notification. BitGo retries
+    /// deliveries and sends one per confirmation threshold, so the transfer id alone
+    /// is not enough to tell two events apart.
+    ///
+    /// Returns `None` if the notification carries no transfer, pending approval,
+    /// hash or address to identify it by.
+    pub fn event_key(&self) -> Option<EventKey> {
+        let id = self
+            .transfer
+            .as_ref()
+            .or(self.pending_approval_id.as_ref())
+            .or(self.hash.as_ref())
+            .or(self.address.as_ref())
+            .filter(|id| !id.is_empty())?;
+        Some(EventKey {
+            webhook_type: self.webhook_type.clone(),
+            id: id.clone(),
+            state: self.state.clone().unwrap_or_default(),
+            confirmations: self.confirmations.unwrap_or(0),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EventKey {
+    pub webhook_type: String,
+    /// Transfer id, pending approval id, hash or address, whichever identifies
+    /// the notification.
+    pub id: String,
+    pub state: String,
+    pub confirmations: u32,
+}
+
+impl std::fmt::Display for EventKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}:{}",
+            self.webhook_type, self.id, self.state, self.confirmations
+        )
+    }
+}
+
+/// Storage for the events a receiver has already handled.
+///
+/// `claim` reserves a key before the handler runs. A claimed key is either
+/// `complete`d once the handler succeeds, which records it permanently, or
+/// `release`d so a later delivery of the same event can be handled again.
+#[async_trait]
+pub trait DedupStore: Send + Sync {
+    /// Returns `false` if the event was already processed or is being processed.
+    async fn claim(&self, key: &EventKey) -> Result<bool>;
+    async fn complete(&self, key: &EventKey) -> Result<()>;
+    async fn release(&self, key: &EventKey) -> Result<()>;
+    async fn is_processed(&self, key: &EventKey) -> Result<bool>;
+}
+
+#[derive(Debug, Default)]
+struct KeySets {
+    processed: HashSet<String>,
+    in_flight: HashSet<String>,
+}
+
+impl KeySets {
+    fn claim(&mut self, key: String) -> bool {
+        if self.processed.contains(&key) || self.in_flight.contains(&key) {
+            return false;
+        }
+        self.in_flight.insert(key)
+    }
+}
+
+/// Keeps processed events in memory, so duplicates are only caught for the
+/// lifetime of the process.
+#[derive(Debug, Default)]
+pub struct MemoryDedupStore {
+    keys: Mutex<KeySets>,
+}
+
+impl MemoryDedupStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DedupStore for MemoryDedupStore {
+    async fn claim(&self, key: &EventKey) -> Result<bool> {
+        Ok(self.keys.lock().unwrap().claim(key.to_string()))
+    }
+
+    async fn complete(&self, key: &EventKey) -> Result<()> {
+        let mut keys = self.keys.lock().unwrap();
+        let key = key.to_string();
+        keys.in_flight.remove(&key);
+        keys.processed.insert(key);
+        Ok(())
+    }
+
+    async fn release(&self, key: &EventKey) -> Result<()> {
+        self.keys.lock().unwrap().in_flight.remove(&key.to_string());
+        Ok(())
+    }
+
+    async fn is_processed(&self, key: &EventKey) -> Result<bool> {
+        Ok(self
+            .keys
+            .lock()
+            .unwrap()
+            .processed
+            .contains(&key.to_string()))
+    }
+}
+
+/// Appends every processed event to a file, one key per line, and reloads it on
+/// open so duplicates are caught across restarts.
+#[derive(Debug)]
+pub struct FileDedupStore {
+    path: PathBuf,
+    keys: Mutex<KeySets>,
+    /// Serializes appends so lines from concurrent completions don't interleave.
+    writer: tokio::sync::Mutex<()>,
+}
+
+impl FileDedupStore {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut keys = KeySets::default();
+        if path.exists() {
+            let reader = BufReader::new(File::open(&path)?);
+            for line in reader.lines() {
+                let line = line?;
+                if !line.is_empty() {
+                    keys.processed.insert(line);
+                }
+            }
+        }
+        Ok(FileDedupStore {
+            path,
+            keys: Mutex::new(keys),
+            writer: tokio::sync::Mutex::new(()),
+        })
+    }
+}
+
+#[async_trait]
+impl DedupStore for FileDedupStore {
+    async fn claim(&self, key: &EventKey) -> Result<bool> {
+        Ok(self.keys.lock().unwrap().claim(key.to_string()))
+    }
+
+    async fn complete(&self, key: &EventKey) -> Result<()> {
+        let key = key.to_string();
+        {
+            let _writer = self.writer.lock().await;
+            append_line(self.path.clone(), key.clone()).await?;
+        }
+        let mut keys = self.keys.lock().unwrap();
+        keys.in_flight.remove(&key);
+        keys.processed.insert(key);
+        Ok(())
+    }
+
+    async fn release(&self, key: &EventKey) -> Result<()> {
+        self.keys.lock().unwrap().in_flight.remove(&key.to_string());
+        Ok(())
+    }
+
+    async fn is_processed(&self, key: &EventKey) -> Result<bool> {
+        Ok(self
+            .keys
+            .lock()
+            .unwrap()
+            .processed
+            .contains(&key.to_string()))
+    }
+}
+
+/// Dispatches webhook notifications to a handler once per logical event.
+pub struct WebhookReceiver<S: DedupStore> {
+    store: S,
+}
+
+impl<S: DedupStore> WebhookReceiver<S> {
+    pub fn new(store: S) -> Self {
+        WebhookReceiver { store }
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Runs `handler` unless the event was already handled. Returns `true` if the
+    /// handler ran and succeeded. If the handler fails the event is released so
+    /// BitGo's next retry is dispatched again. Notifications without an
+    /// [`EventKey`] are always dispatched.
+    pub async fn dispatch<F, Fut>(
+        &self,
+        notification: &WebhookNotification,
+        handler: F,
+    ) -> Result<bool>
+    where
+        F: FnOnce(WebhookNotification) -> Fut,
+        Fut: Future<Output = Result<()>>,
+    {
+        let key = match notification.event_key() {
+            Some(key) => key,
+            None => {
+                log::warn!(
+                    "{} webhook carries no id, dispatching without dedup",
+                    notification.webhook_type
+                );
+                handler(notification.clone()).await?;
+                return Ok(true);
+            }
+        };
+        if !self.store.claim(&key).await? {
+            log::debug!("skipping duplicate webhook event {}", key);
+            return Ok(false);
+        }
+        match handler(notification.clone()).await {
+            Ok(()) => {
+                self.store.complete(&key).await?;
+                Ok(true)
+            }
+            Err(err) => {
+                self.store.release(&key).await?;
+                Err(err)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use serde_json::json;
+
+    fn notification(state: &str, confirmations: u32) -> WebhookNotification {
+        serde_json::from_value(json!({
+            "type": "transfer",
+            "coin": "tbtc",
+            "wallet": "wallet",
+            "transfer": "transfer",
+            "hash": "hash",
+            "state": state,
+            "confirmations": confirmations,
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_once() {
+        let receiver = WebhookReceiver::new(MemoryDedupStore::new());
+        let first = notification("confirmed", 1);
+
+        assert!(receiver
+            .dispatch(&first, |_| async { Ok(()) })
+            .await
+            .unwrap());
+        assert!(!receiver
+            .dispatch(&first, |_| async { Ok(()) })
+            .await
+            .unwrap());
+        assert!(receiver
+            .dispatch(&notification("confirmed", 3), |_| async { Ok(()) })
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_failed_handler_is_retried() {
+        let receiver = WebhookReceiver::new(MemoryDedupStore::new());
+        let event = notification("confirmed", 1);
+
+        let res = receiver
+            .dispatch(&event, |_| async {
+                Err(Error::BitgoError {
+                    msg: "failed".to_string(),
+                })
+            })
+            .await;
+        assert!(res.is_err());
+        assert!(receiver
+            .dispatch(&event, |_| async { Ok(()) })
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_file_store_survives_reopen() {
+        let path = std::env::temp_dir().join(format!("bitgo_dedup_{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let key = notification("confirmed", 1).event_key().unwrap();
+
+        let store = FileDedupStore::open(&path).unwrap();
+        assert!(store.claim(&key).await.unwrap());
+        store.complete(&key).await.unwrap();
+
+        let reopened = FileDedupStore::open(&path).unwrap();
+        assert!(reopened.is_processed(&key).await.unwrap());
+        assert!(!reopened.claim(&key).await.unwrap());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_pending_approvals_are_told_apart() {
+        let receiver = WebhookReceiver::new(MemoryDedupStore::new());
+        let approval = |id: &str| -> WebhookNotification {
+            serde_json::from_value(json!({
+                "type": "pendingapproval",
+                "pendingApprovalId": id,
+                "state": "pending",
+            }))
+            .unwrap()
+        };
+
+        for id in ["approval1", "approval2"].iter() {
+            assert!(receiver
+                .dispatch(&approval(id), |_| async { Ok(()) })
+                .await
+                .unwrap());
+        }
+        assert!(!receiver
+            .dispatch(&approval("approval1"), |_| async { Ok(()) })
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_notification_without_id_skips_dedup() {
+        let receiver = WebhookReceiver::new(MemoryDedupStore::new());
+        let event: WebhookNotification =
+            serde_json::from_value(json!({ "type": "block", "state": "new" })).unwrap();
+
+        assert!(event.event_key().is_none());
+        for _ in 0..2 {
+            assert!(receiver
+                .dispatch(&event, |_| async { Ok(()) })
+                .await
+                .unwrap());
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use crate::client::BitGoClient;
+use crate::error::Result;
+use crate::user::User;
+use crate::wallet::{WalletList, WalletListOptions};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Enterprise {
+    pub id: String,
+    pub name: Option<String>,
+    pub organization: Option<String>,
+    pub primary_contact: Option<String>,
+    pub email_domain: Option<String>,
+    pub created: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnterpriseUsers {
+    #[serde(default)]
+    pub admin_users: Vec<User>,
+    #[serde(default)]
+    pub non_admin_users: Vec<User>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddEnterpriseUserOptions {
+    /// Email address of the user to add.
+    pub username: String,
+    /// `admin`, or omitted for a non-admin member.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permission: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EnterpriseList {
+    #[serde(default)]
+    enterprises: Vec<Enterprise>,
+}
+
+#[async_trait]
+pub trait BitGoEnterpriseAPI {
+    async fn list_enterprises(&self) -> Result<Vec<Enterprise>>;
+    async fn get_enterprise(&self, enterprise_id: &str) -> Result<Enterprise>;
+    async fn list_enterprise_users(&self, enterprise_id: &str) -> Result<EnterpriseUsers>;
+    async fn add_enterprise_user(
+        &self,
+        enterprise_id: &str,
+        options: &AddEnterpriseUserOptions,
+    ) -> Result<serde_json::Value>;
+    async fn remove_enterprise_user(
+        &self,
+        enterprise_id: &str,
+        username: &str,
+    ) -> Result<serde_json::Value>;
+    async fn list_enterprise_wallets(
+        &self,
+        enterprise_id: &str,
+        options: &WalletListOptions,
+    ) -> Result<WalletList>;
+}
+
+#[async_trait]
+impl BitGoEnterpriseAPI for BitGoClient {
+    /// This API call lists the enterprises the user is a member of.
+    async fn list_enterprises(&self) -> Result<Vec<Enterprise>> {
+        let request_url = format!("{url}/api/v2/enterprise", url = self.endpoint);
+        let enterprises: EnterpriseList =
+            serde_json::from_value(self.get_api(&request_url, &json!({})).await?)?;
+        Ok(enterprises.enterprises)
+    }
+
+    async fn get_enterprise(&self, enterprise_id: &str) -> Result<Enterprise> {
+        let request_url = format!(
+            "{url}/api/v2/enterprise/{enterprise_id}",
+            url = self.endpoint,
+            enterprise_id = enterprise_id,
+        );
+        let enterprise = self.get_api(&request_url, &json!({})).await?;
+        Ok(serde_json::from_value(enterprise)?)
+    }
+
+    async fn list_enterprise_users(&self, enterprise_id: &str) -> Result<EnterpriseUsers> {
+        let request_url = format!(
+            "{url}/api/v2/enterprise/{enterprise_id}/user",
+            url = self.endpoint,
+            enterprise_id = enterprise_id,
+        );
+        let users = self.get_api(&request_url, &json!({})).await?;
+        Ok(serde_json::from_value(users)?)
+    }
+
+    /// This API call adds a user to the enterprise. Only enterprise admins may add users.
+    async fn add_enterprise_user(
+        &self,
+        enterprise_id: &str,
+        options: &AddEnterpriseUserOptions,
+    ) -> Result<serde_json::Value> {
+        let request_url = format!(
+            "{url}/api/v2/enterprise/{enterprise_id}/user",
+            url = self.endpoint,
+            enterprise_id = enterprise_id,
+        );
+        self.post_api(&request_url, options).await
+    }
+
+    async fn remove_enterprise_user(
+        &self,
+        enterprise_id: &str,
+        username: &str,
+    ) -> Result<serde_json::Value> {
+        let request_url = format!(
+            "{url}/api/v2/enterprise/{enterprise_id}/user",
+            url = self.endpoint,
+            enterprise_id = enterprise_id,
+        );
+        self.delete_api(&request_url, &json!({ "username": username }))
+            .await
+    }
+
+    /// This API call lists the wallets of all coins in the enterprise. The
+    /// `enterprise` field of `options` is overridden.
+    async fn list_enterprise_wallets(
+        &self,
+        enterprise_id: &str,
+        options: &WalletListOptions,
+    ) -> Result<WalletList> {
+        let request_url = format!("{url}/api/v2/wallets", url = self.endpoint);
+        let options = WalletListOptions {
+            enterprise: Some(enterprise_id.to_string()),
+            ..options.clone()
+        };
+        let wallets = self.get_api(&request_url, &options).await?;
+        Ok(serde_json::from_value(wallets)?)
+    }
+}
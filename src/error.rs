@@ -10,6 +10,23 @@ pub enum Error {
 
     #[error("bitgo Error: {msg}")]
     BitgoError { msg: String },
+
+    /// The session must be unlocked with an OTP before retrying the call.
+    #[error("bitgo session needs unlock: {msg}")]
+    NeedsUnlock { msg: String },
+
+    #[error("json Error: {msg}")]
+    JsonError { msg: String },
+
+    #[error("io Error: {msg}")]
+    IoError { msg: String },
+
+    #[error("invalid policy spec: {msg}")]
+    InvalidPolicySpec { msg: String },
+
+    /// The forwarder of a newly created address wasn't deployed in time.
+    #[error("forwarder of address {address} is still being deployed")]
+    ForwarderPending { address: String },
 }
 
 impl From<reqwest::Error> for Error {
@@ -20,5 +37,20 @@ impl From<reqwest::Error> for Error {
     }
 }
 
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::JsonError {
+            msg: format!("{}", err),
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::IoError {
+            msg: format!("{}", err),
+        }
+    }
+}
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,762 @@
+use crate::error::Result;
+use crate::transfer::{
+    AccelerateTransactionOptions, BitGoTransferAPI, FeeEstimateOptions, Transfer,
+    TransferListOptions,
+};
+use crate::util::append_line;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// How a stuck transaction of a coin is sped up.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BumpStrategy {
+    /// Replace the transaction with one paying up to `fee_multiplier` times the fee.
+    Rbf { fee_multiplier: f64 },
+    /// Spend the change output in a child paying the estimated fee rate.
+    Cpfp,
+    /// Resend an account-based transaction at the estimated gas price through `change_fee`.
+    ChangeFee,
+}
+
+/// When a transfer of a coin counts as stuck and how far its fee may be raised.
+#[derive(Debug, Clone)]
+pub struct StuckTransferPolicy {
+    /// Age after which an unconfirmed outgoing transfer is considered stuck.
+    pub min_age: Duration,
+    pub strategy: BumpStrategy,
+    /// Highest fee rate a bump may pay, in base units per kB, or the highest
+    /// gas price in wei for [`BumpStrategy::ChangeFee`].
+    pub max_fee_rate: u64,
+    /// Highest fee the CPFP child may pay, in base units.
+    pub max_fee: u64,
+    /// Number of bumps after which a transfer is left alone.
+    pub max_bumps: u32,
+}
+
+#[derive(Clone)]
+pub struct FeeBumpConfig {
+    /// Wallets to watch, as `(wallet_id, identifier)`.
+    pub wallets: Vec<(String, String)>,
+    /// Policies by coin. Wallets of coins without a policy are not checked.
+    pub policies: HashMap<String, StuckTransferPolicy>,
+    /// Record decisions without sending any bump.
+    pub dry_run: bool,
+    /// Needed by BitGo Express to sign RBF and CPFP transactions.
+    pub wallet_passphrase: String,
+}
+
+impl std::fmt::Debug for FeeBumpConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FeeBumpConfig")
+            .field("wallets", &self.wallets)
+            .field("policies", &self.policies)
+            .field("dry_run", &self.dry_run)
+            .field("wallet_passphrase", &"<redacted>")
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum FeeBumpAction {
+    Skipped {
+        reason: String,
+    },
+    /// The bump that was sent, or would have been sent in dry-run mode.
+    Bumped {
+        strategy: BumpStrategy,
+        fee_rate: u64,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+/// One entry of the audit log. Every stuck transfer the worker looks at yields
+/// exactly one decision per run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeBumpDecision {
+    pub time: DateTime<Utc>,
+    pub wallet_id: String,
+    pub coin: String,
+    pub transfer_id: String,
+    pub txid: Option<String>,
+    pub age_secs: u64,
+    /// Fee rate per kB, or gas price in wei for [`BumpStrategy::ChangeFee`].
+    pub current_fee_rate: Option<u64>,
+    pub estimated_fee_rate: Option<u64>,
+    pub dry_run: bool,
+    #[serde(flatten)]
+    pub action: FeeBumpAction,
+}
+
+/// Record of the worker's decisions. The log is also where the worker learns
+/// how often a transfer was already bumped, so it must survive restarts for
+/// `max_bumps` to hold across them.
+#[async_trait]
+pub trait FeeBumpAuditLog: Send + Sync {
+    async fn record(&self, decision: &FeeBumpDecision) -> Result<()>;
+    /// Bumps actually sent for a transfer, not counting dry runs.
+    async fn bump_history(&self, transfer_id: &str) -> Result<BumpHistory>;
+}
+
+/// Bumps sent for one transfer so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BumpHistory {
+    pub count: u32,
+    /// Fee rate of the last bump sent. A CPFP child doesn't change the fee rate
+    /// BitGo reports for its parent, so this is the rate the transfer effectively pays.
+    pub last_fee_rate: Option<u64>,
+}
+
+impl BumpHistory {
+    fn add(&mut self, decision: &FeeBumpDecision) {
+        if decision.dry_run {
+            return;
+        }
+        if let FeeBumpAction::Bumped { fee_rate, .. } = decision.action {
+            self.count += 1;
+            self.last_fee_rate = Some(fee_rate);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MemoryFeeBumpAuditLog {
+    decisions: Mutex<Vec<FeeBumpDecision>>,
+}
+
+impl MemoryFeeBumpAuditLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn decisions(&self) -> Vec<FeeBumpDecision> {
+        self.decisions.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl FeeBumpAuditLog for MemoryFeeBumpAuditLog {
+    async fn record(&self, decision: &FeeBumpDecision) -> Result<()> {
+        self.decisions.lock().unwrap().push(decision.clone());
+        Ok(())
+    }
+
+    async fn bump_history(&self, transfer_id: &str) -> Result<BumpHistory> {
+        let mut history = BumpHistory::default();
+        for decision in self.decisions.lock().unwrap().iter() {
+            if decision.transfer_id == transfer_id {
+                history.add(decision);
+            }
+        }
+        Ok(history)
+    }
+}
+
+/// Appends every decision to a file as one JSON object per line, and reloads
+/// the bump history from it on open. The history in memory is updated even if
+/// the append fails, so a running worker still respects `max_bumps`.
+#[derive(Debug)]
+pub struct FileFeeBumpAuditLog {
+    path: PathBuf,
+    bumps: Mutex<HashMap<String, BumpHistory>>,
+    /// Serializes appends so lines from concurrent records don't interleave.
+    writer: tokio::sync::Mutex<()>,
+}
+
+impl FileFeeBumpAuditLog {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut bumps: HashMap<String, BumpHistory> = HashMap::new();
+        if path.exists() {
+            let reader = BufReader::new(File::open(&path)?);
+            for line in reader.lines() {
+                let line = line?;
+                if line.is_empty() {
+                    continue;
+                }
+                let decision: FeeBumpDecision = serde_json::from_str(&line)?;
+                bumps
+                    .entry(decision.transfer_id.clone())
+                    .or_default()
+                    .add(&decision);
+            }
+        }
+        Ok(FileFeeBumpAuditLog {
+            path,
+            bumps: Mutex::new(bumps),
+            writer: tokio::sync::Mutex::new(()),
+        })
+    }
+}
+
+#[async_trait]
+impl FeeBumpAuditLog for FileFeeBumpAuditLog {
+    async fn record(&self, decision: &FeeBumpDecision) -> Result<()> {
+        self.bumps
+            .lock()
+            .unwrap()
+            .entry(decision.transfer_id.clone())
+            .or_default()
+            .add(decision);
+        let line = serde_json::to_string(decision)?;
+        let _writer = self.writer.lock().await;
+        append_line(self.path.clone(), line).await
+    }
+
+    async fn bump_history(&self, transfer_id: &str) -> Result<BumpHistory> {
+        Ok(self
+            .bumps
+            .lock()
+            .unwrap()
+            .get(transfer_id)
+            .copied()
+            .unwrap_or_default())
+    }
+}
+
+fn is_unconfirmed_send(transfer: &Transfer) -> bool {
+    transfer.transfer_type.as_deref() == Some("send")
+        && matches!(
+            transfer.state.as_deref(),
+            Some("signed") | Some("unconfirmed")
+        )
+}
+
+/// Fee rate the transfer pays, in the unit of the strategy: the gas price for
+/// [`BumpStrategy::ChangeFee`], the fee rate per kB otherwise.
+fn current_fee_rate(transfer: &Transfer, strategy: BumpStrategy) -> Option<u64> {
+    match strategy {
+        BumpStrategy::ChangeFee => transfer
+            .gas_price
+            .as_ref()
+            .and_then(|gas_price| gas_price.to_u128())
+            .and_then(|gas_price| u64::try_from(gas_price).ok()),
+        _ => transfer.fee_rate_per_kb(),
+    }
+}
+
+/// Finds outgoing transfers that stay unconfirmed past the age set for their
+/// coin and raises their fee to the current estimate, within the caps of the
+/// coin's [`StuckTransferPolicy`].
+///
+/// Transfers whose current fee rate can't be determined are skipped, as are
+/// transfers already paying the estimate or the cap, whether by their own fee
+/// or by the last bump sent for them.
+pub struct FeeBumpWorker<C, L> {
+    client: C,
+    audit_log: L,
+    config: FeeBumpConfig,
+}
+
+impl<C, L> FeeBumpWorker<C, L>
+where
+    C: BitGoTransferAPI + Sync + Send + 'static,
+    L: FeeBumpAuditLog + 'static,
+{
+    pub fn new(client: C, audit_log: L, config: FeeBumpConfig) -> Self {
+        FeeBumpWorker {
+            client,
+            audit_log,
+            config,
+        }
+    }
+
+    /// Checks every configured wallet once and returns the decisions taken.
+    /// A wallet that can't be checked doesn't keep the others from being checked;
+    /// the first error is returned, and the decisions taken are still in the audit log.
+    pub async fn run_once(&self) -> Result<Vec<FeeBumpDecision>> {
+        let mut decisions = Vec::new();
+        let mut first_error = None;
+        for (wallet_id, identifier) in self.config.wallets.iter() {
+            match self.check_wallet(wallet_id, identifier).await {
+                Ok(mut wallet_decisions) => decisions.append(&mut wallet_decisions),
+                Err(err) => {
+                    log::error!("failed to check transfers of wallet {}: {}", wallet_id, err);
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+        match first_error {
+            Some(err) => Err(err),
+            None => Ok(decisions),
+        }
+    }
+
+    /// Runs [`FeeBumpWorker::run_once`] every `interval`.
+    pub fn spawn(self: Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                if let Err(err) = self.run_once().await {
+                    log::error!("fee bump run failed: {}", err);
+                }
+                tokio::time::sleep(interval).await;
+            }
+        })
+    }
+
+    async fn check_wallet(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+    ) -> Result<Vec<FeeBumpDecision>> {
+        let policy = match self.config.policies.get(identifier) {
+            Some(policy) => policy,
+            None => return Ok(Vec::new()),
+        };
+        let mut options = TransferListOptions {
+            transfer_type: Some("send".to_string()),
+            state: vec!["signed".to_string(), "unconfirmed".to_string()],
+            ..Default::default()
+        };
+        let mut transfers = Vec::new();
+        loop {
+            let page = self
+                .client
+                .list_transfers(wallet_id, identifier, &options)
+                .await?;
+            transfers.extend(page.transfers);
+            match page.next_batch_prev_id {
+                Some(prev_id) => options.prev_id = Some(prev_id),
+                None => break,
+            }
+        }
+        let now = Utc::now();
+        let stuck: Vec<(Transfer, Duration)> = transfers
+            .into_iter()
+            .filter(is_unconfirmed_send)
+            .filter_map(|transfer| {
+                let age = (now - transfer.date?).to_std().ok()?;
+                Some((transfer, age)).filter(|(_, age)| *age >= policy.min_age)
+            })
+            .collect();
+        if stuck.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let estimate = self.estimate_fee_rate(identifier, policy.strategy).await?;
+        let mut decisions = Vec::new();
+        for (transfer, age) in stuck {
+            let action = self
+                .decide_and_bump(wallet_id, identifier, policy, &transfer, estimate)
+                .await;
+            let decision = FeeBumpDecision {
+                time: Utc::now(),
+                wallet_id: wallet_id.to_string(),
+                coin: identifier.to_string(),
+                transfer_id: transfer.id.clone(),
+                txid: transfer.txid.clone(),
+                age_secs: age.as_secs(),
+                current_fee_rate: current_fee_rate(&transfer, policy.strategy),
+                estimated_fee_rate: estimate,
+                dry_run: self.config.dry_run,
+                action,
+            };
+            log::info!("fee bump decision {:?}", decision);
+            // A bump that was sent must not keep the remaining transfers from
+            // being checked just because it couldn't be logged.
+            if let Err(err) = self.audit_log.record(&decision).await {
+                log::error!(
+                    "failed to record fee bump decision for transfer {}: {}",
+                    decision.transfer_id,
+                    err
+                );
+            }
+            decisions.push(decision);
+        }
+        Ok(decisions)
+    }
+
+    async fn estimate_fee_rate(
+        &self,
+        identifier: &str,
+        strategy: BumpStrategy,
+    ) -> Result<Option<u64>> {
+        let options = FeeEstimateOptions {
+            num_blocks: Some(2),
+            ..Default::default()
+        };
+        let fee = self.client.get_fee(identifier, &options).await?;
+        let rate = match strategy {
+            BumpStrategy::Cpfp => fee.cpfp_fee_per_kb.or(fee.fee_per_kb),
+            BumpStrategy::Rbf { .. } => fee.fee_per_kb,
+            BumpStrategy::ChangeFee => fee
+                .fee_estimate
+                .or(fee.eip1559.and_then(|eip1559| eip1559.max_fee_per_gas)),
+        };
+        Ok(rate
+            .and_then(|rate| rate.to_u128())
+            .and_then(|rate| u64::try_from(rate).ok()))
+    }
+
+    async fn decide_and_bump(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        policy: &StuckTransferPolicy,
+        transfer: &Transfer,
+        estimate: Option<u64>,
+    ) -> FeeBumpAction {
+        let skip = |reason: &str| FeeBumpAction::Skipped {
+            reason: reason.to_string(),
+        };
+        let txid = match transfer.txid.as_deref() {
+            Some(txid) => txid,
+            None => return skip("transfer has no txid"),
+        };
+        let current = match current_fee_rate(transfer, policy.strategy) {
+            Some(current) => current,
+            None => return skip("current fee rate unknown"),
+        };
+        let estimate = match estimate {
+            Some(estimate) => estimate,
+            None => return skip("fee estimate unavailable"),
+        };
+        let history = match self.audit_log.bump_history(&transfer.id).await {
+            Ok(history) => history,
+            Err(err) => {
+                return FeeBumpAction::Failed {
+                    error: err.to_string(),
+                }
+            }
+        };
+        if history.count >= policy.max_bumps {
+            return skip("maximum number of bumps reached");
+        }
+        let target = estimate.min(policy.max_fee_rate);
+        if current.max(history.last_fee_rate.unwrap_or(0)) >= target {
+            return skip("fee rate already at estimate or cap");
+        }
+
+        let (strategy, fee_rate) = match policy.strategy {
+            BumpStrategy::Rbf { fee_multiplier } => {
+                let fee_multiplier = fee_multiplier.min(target as f64 / current.max(1) as f64);
+                if fee_multiplier <= 1.0 {
+                    return skip("fee multiplier capped to 1");
+                }
+                (
+                    BumpStrategy::Rbf { fee_multiplier },
+                    (current as f64 * fee_multiplier) as u64,
+                )
+            }
+            strategy => (strategy, target),
+        };
+        if self.config.dry_run {
+            return FeeBumpAction::Bumped { strategy, fee_rate };
+        }
+
+        let result = match strategy {
+            BumpStrategy::Rbf { fee_multiplier } => self
+                .client
+                .accelerate_transaction(
+                    wallet_id,
+                    identifier,
+                    &AccelerateTransactionOptions::rbf(
+                        txid,
+                        fee_multiplier,
+                        &self.config.wallet_passphrase,
+                    ),
+                )
+                .await
+                .map(|_| ()),
+            BumpStrategy::Cpfp => self
+                .client
+                .accelerate_transaction(
+                    wallet_id,
+                    identifier,
+                    &AccelerateTransactionOptions::cpfp(
+                        txid,
+                        fee_rate,
+                        policy.max_fee,
+                        &self.config.wallet_passphrase,
+                    ),
+                )
+                .await
+                .map(|_| ()),
+            BumpStrategy::ChangeFee => self
+                .client
+                .change_fee(identifier, wallet_id, txid, &fee_rate.to_string())
+                .await
+                .map(|_| ()),
+        };
+        match result {
+            Ok(()) => FeeBumpAction::Bumped { strategy, fee_rate },
+            Err(err) => FeeBumpAction::Failed {
+                error: err.to_string(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockBitGoClient;
+    use serde_json::json;
+
+    fn config(strategy: BumpStrategy, dry_run: bool) -> FeeBumpConfig {
+        let mut policies = HashMap::new();
+        policies.insert(
+            "tbtc".to_string(),
+            StuckTransferPolicy {
+                min_age: Duration::from_secs(3600),
+                strategy,
+                max_fee_rate: 40000,
+                max_fee: 100000,
+                max_bumps: 1,
+            },
+        );
+        FeeBumpConfig {
+            wallets: vec![("wallet".to_string(), "tbtc".to_string())],
+            policies,
+            dry_run,
+            wallet_passphrase: "secret".to_string(),
+        }
+    }
+
+    fn mock_transfers(mock: &mut MockBitGoClient) {
+        let old = (Utc::now() - chrono::Duration::hours(3)).to_rfc3339();
+        let recent = Utc::now().to_rfc3339();
+        // The stuck transfer is on the second page.
+        mock.expect_list_transfers()
+            .withf(|_, _, options| {
+                options.transfer_type.as_deref() == Some("send")
+                    && options.state == vec!["signed".to_string(), "unconfirmed".to_string()]
+            })
+            .returning(move |_, _, options| {
+                let page = match options.prev_id.as_deref() {
+                    None => json!({
+                        "transfers": [
+                            { "id": "recent", "txid": "tx2", "type": "send", "state": "signed",
+                              "date": recent, "feeString": "2000", "vSize": 200 },
+                            { "id": "received", "txid": "tx3", "type": "receive",
+                              "state": "unconfirmed", "date": old },
+                        ],
+                        "nextBatchPrevId": "page2",
+                    }),
+                    Some(_) => json!({ "transfers": [
+                        { "id": "stuck", "txid": "tx1", "type": "send", "state": "signed",
+                          "date": old, "feeString": "2000", "vSize": 200 },
+                    ]}),
+                };
+                Ok(serde_json::from_value(page).unwrap())
+            });
+        mock.expect_get_fee()
+            .returning(|_, _| Ok(serde_json::from_value(json!({ "feePerKb": 50000 })).unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_bumps_stuck_transfer_within_cap() {
+        let mut mock = MockBitGoClient::new();
+        mock_transfers(&mut mock);
+        mock.expect_accelerate_transaction()
+            .withf(|_, _, options| {
+                options.cpfp_tx_ids == vec!["tx1".to_string()]
+                    && options.cpfp_fee_rate == Some(40000)
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(serde_json::from_value(json!({})).unwrap()));
+        let worker = FeeBumpWorker::new(
+            mock,
+            MemoryFeeBumpAuditLog::new(),
+            config(BumpStrategy::Cpfp, false),
+        );
+
+        let decisions = worker.run_once().await.unwrap();
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(decisions[0].transfer_id, "stuck");
+        assert_eq!(decisions[0].current_fee_rate, Some(10000));
+        assert_eq!(
+            decisions[0].action,
+            FeeBumpAction::Bumped {
+                strategy: BumpStrategy::Cpfp,
+                fee_rate: 40000
+            }
+        );
+
+        let decisions = worker.run_once().await.unwrap();
+        assert!(matches!(decisions[0].action, FeeBumpAction::Skipped { .. }));
+        assert_eq!(worker.audit_log.decisions().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cpfp_not_repeated_once_target_paid() {
+        let mut mock = MockBitGoClient::new();
+        mock_transfers(&mut mock);
+        mock.expect_accelerate_transaction()
+            .times(1)
+            .returning(|_, _, _| Ok(serde_json::from_value(json!({})).unwrap()));
+        let mut config = config(BumpStrategy::Cpfp, false);
+        config.policies.get_mut("tbtc").unwrap().max_bumps = 3;
+        let worker = FeeBumpWorker::new(mock, MemoryFeeBumpAuditLog::new(), config);
+
+        assert!(matches!(
+            worker.run_once().await.unwrap()[0].action,
+            FeeBumpAction::Bumped { .. }
+        ));
+        for _ in 0..2 {
+            let decisions = worker.run_once().await.unwrap();
+            assert_eq!(decisions[0].current_fee_rate, Some(10000));
+            assert_eq!(
+                decisions[0].action,
+                FeeBumpAction::Skipped {
+                    reason: "fee rate already at estimate or cap".to_string()
+                }
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_sends_nothing() {
+        let mut mock = MockBitGoClient::new();
+        mock_transfers(&mut mock);
+        mock.expect_accelerate_transaction().times(0);
+        let worker = FeeBumpWorker::new(
+            mock,
+            MemoryFeeBumpAuditLog::new(),
+            config(
+                BumpStrategy::Rbf {
+                    fee_multiplier: 2.0,
+                },
+                true,
+            ),
+        );
+
+        let decisions = worker.run_once().await.unwrap();
+        assert!(decisions[0].dry_run);
+        assert_eq!(
+            decisions[0].action,
+            FeeBumpAction::Bumped {
+                strategy: BumpStrategy::Rbf {
+                    fee_multiplier: 2.0
+                },
+                fee_rate: 20000
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_change_fee_bumps_account_coin_gas_price() {
+        let mut policies = HashMap::new();
+        policies.insert(
+            "teth".to_string(),
+            StuckTransferPolicy {
+                min_age: Duration::from_secs(600),
+                strategy: BumpStrategy::ChangeFee,
+                max_fee_rate: 50_000_000_000,
+                max_fee: 0,
+                max_bumps: 1,
+            },
+        );
+        let config = FeeBumpConfig {
+            wallets: vec![("wallet".to_string(), "teth".to_string())],
+            policies,
+            dry_run: false,
+            wallet_passphrase: "secret".to_string(),
+        };
+        let old = (Utc::now() - chrono::Duration::hours(1)).to_rfc3339();
+        let mut mock = MockBitGoClient::new();
+        mock.expect_list_transfers().returning(move |_, _, _| {
+            Ok(serde_json::from_value(json!({ "transfers": [
+                { "id": "stuck", "txid": "0xabc", "type": "send", "state": "signed",
+                  "date": old, "feeString": "420000000000000", "gasPrice": "20000000000" },
+            ]}))
+            .unwrap())
+        });
+        mock.expect_get_fee().returning(|_, _| {
+            Ok(serde_json::from_value(json!({ "feeEstimate": "30000000000" })).unwrap())
+        });
+        mock.expect_change_fee()
+            .withf(|identifier, wallet_id, tx_id, fee| {
+                identifier == "teth"
+                    && wallet_id == "wallet"
+                    && tx_id == "0xabc"
+                    && fee == "30000000000"
+            })
+            .times(1)
+            .returning(|_, _, _, _| Ok(json!({})));
+        let worker = FeeBumpWorker::new(mock, MemoryFeeBumpAuditLog::new(), config);
+
+        let decisions = worker.run_once().await.unwrap();
+        assert_eq!(decisions[0].current_fee_rate, Some(20_000_000_000));
+        assert_eq!(
+            decisions[0].action,
+            FeeBumpAction::Bumped {
+                strategy: BumpStrategy::ChangeFee,
+                fee_rate: 30_000_000_000
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_audit_log_failure_does_not_stop_run() {
+        let path = std::env::temp_dir().join(format!("bitgo_fee_bump_dir_{}", std::process::id()));
+        let audit_log = FileFeeBumpAuditLog::open(&path).unwrap();
+        // Appending to a directory fails.
+        std::fs::create_dir_all(&path).unwrap();
+
+        let mut mock = MockBitGoClient::new();
+        mock_transfers(&mut mock);
+        mock.expect_accelerate_transaction()
+            .times(1)
+            .returning(|_, _, _| Ok(serde_json::from_value(json!({})).unwrap()));
+        let worker = FeeBumpWorker::new(mock, audit_log, config(BumpStrategy::Cpfp, false));
+
+        assert!(matches!(
+            worker.run_once().await.unwrap()[0].action,
+            FeeBumpAction::Bumped { .. }
+        ));
+        assert!(matches!(
+            worker.run_once().await.unwrap()[0].action,
+            FeeBumpAction::Skipped { .. }
+        ));
+        std::fs::remove_dir(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bump_count_survives_restart() {
+        let path = std::env::temp_dir().join(format!("bitgo_fee_bump_{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut mock = MockBitGoClient::new();
+        mock_transfers(&mut mock);
+        mock.expect_accelerate_transaction()
+            .times(1)
+            .returning(|_, _, _| Ok(serde_json::from_value(json!({})).unwrap()));
+        let worker = FeeBumpWorker::new(
+            mock,
+            FileFeeBumpAuditLog::open(&path).unwrap(),
+            config(BumpStrategy::Cpfp, false),
+        );
+        assert!(matches!(
+            worker.run_once().await.unwrap()[0].action,
+            FeeBumpAction::Bumped { .. }
+        ));
+
+        let mut mock = MockBitGoClient::new();
+        mock_transfers(&mut mock);
+        mock.expect_accelerate_transaction().times(0);
+        let restarted = FeeBumpWorker::new(
+            mock,
+            FileFeeBumpAuditLog::open(&path).unwrap(),
+            config(BumpStrategy::Cpfp, false),
+        );
+        assert!(matches!(
+            restarted.run_once().await.unwrap()[0].action,
+            FeeBumpAction::Skipped { .. }
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,287 @@
+use crate::client::BitGoClient;
+use crate::error::Result;
+use crate::util::redacted;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Keychain {
+    /// Empty for keys created locally that were not uploaded yet.
+    #[serde(default)]
+    pub id: String,
+    #[serde(rename = "pub")]
+    pub pub_key: Option<String>,
+    pub eth_address: Option<String>,
+    /// `user`, `backup` or `bitgo`.
+    pub source: Option<String>,
+    #[serde(rename = "type")]
+    pub keychain_type: Option<String>,
+    /// Public key shared by all parties of a TSS wallet.
+    pub common_keychain: Option<String>,
+    pub is_bit_go: Option<bool>,
+    pub encrypted_prv: Option<String>,
+    /// Only returned for keys generated locally, e.g. the backup key of a new wallet.
+    pub prv: Option<String>,
+    pub coin_specific: Option<serde_json::Value>,
+}
+
+impl std::fmt::Debug for Keychain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Keychain")
+            .field("id", &self.id)
+            .field("pub_key", &self.pub_key)
+            .field("eth_address", &self.eth_address)
+            .field("source", &self.source)
+            .field("keychain_type", &self.keychain_type)
+            .field("common_keychain", &self.common_keychain)
+            .field("is_bit_go", &self.is_bit_go)
+            .field("encrypted_prv", &redacted(&self.encrypted_prv))
+            .field("prv", &redacted(&self.prv))
+            .field("coin_specific", &self.coin_specific)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeychainList {
+    #[serde(default)]
+    pub keys: Vec<Keychain>,
+    pub next_batch_prev_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeychainListOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev_id: Option<String>,
+}
+
+/// Options for uploading a key created outside of BitGo.
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddKeychainOptions {
+    #[serde(rename = "pub", skip_serializing_if = "Option::is_none")]
+    pub pub_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encrypted_prv: Option<String>,
+    /// `user` or `backup`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub keychain_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub common_keychain: Option<String>,
+}
+
+impl std::fmt::Debug for AddKeychainOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AddKeychainOptions")
+            .field("pub_key", &self.pub_key)
+            .field("encrypted_prv", &redacted(&self.encrypted_prv))
+            .field("source", &self.source)
+            .field("keychain_type", &self.keychain_type)
+            .field("common_keychain", &self.common_keychain)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBitGoKeychainOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enterprise: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBackupKeychainOptions {
+    /// Key recovery service that creates and holds the backup key, e.g. `keyternal`.
+    pub provider: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enterprise: Option<String>,
+}
+
+/// Options for re-encrypting the private key of a keychain with a new passphrase.
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateKeychainOptions {
+    pub old_password: String,
+    pub new_password: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub otp: Option<String>,
+}
+
+impl std::fmt::Debug for UpdateKeychainOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UpdateKeychainOptions")
+            .field("old_password", &"<redacted>")
+            .field("new_password", &"<redacted>")
+            .field("otp", &redacted(&self.otp))
+            .finish()
+    }
+}
+
+#[async_trait]
+pub trait BitGoKeychainAPI {
+    async fn list_keychains(
+        &self,
+        identifier: &str,
+        options: &KeychainListOptions,
+    ) -> Result<KeychainList>;
+    async fn get_keychain(&self, keychain_id: &str, identifier: &str) -> Result<Keychain>;
+    async fn create_keychain(&self, identifier: &str) -> Result<Keychain>;
+    async fn add_keychain(
+        &self,
+        identifier: &str,
+        options: &AddKeychainOptions,
+    ) -> Result<Keychain>;
+    async fn create_bitgo_keychain(
+        &self,
+        identifier: &str,
+        options: &CreateBitGoKeychainOptions,
+    ) -> Result<Keychain>;
+    async fn create_backup_keychain(
+        &self,
+        identifier: &str,
+        options: &CreateBackupKeychainOptions,
+    ) -> Result<Keychain>;
+    async fn update_keychain(
+        &self,
+        keychain_id: &str,
+        identifier: &str,
+        options: &UpdateKeychainOptions,
+    ) -> Result<Keychain>;
+}
+
+#[async_trait]
+impl BitGoKeychainAPI for BitGoClient {
+    /// This API call lists the keychains of the user, one page at a time.
+    async fn list_keychains(
+        &self,
+        identifier: &str,
+        options: &KeychainListOptions,
+    ) -> Result<KeychainList> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/key",
+            url = self.endpoint,
+            coin_type = identifier,
+        );
+        let keys = self.get_api(&request_url, options).await?;
+        Ok(serde_json::from_value(keys)?)
+    }
+
+    async fn get_keychain(&self, keychain_id: &str, identifier: &str) -> Result<Keychain> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/key/{keychain_id}",
+            url = self.endpoint,
+            coin_type = identifier,
+            keychain_id = keychain_id,
+        );
+        let key = self.get_api(&request_url, &json!({})).await?;
+        Ok(serde_json::from_value(key)?)
+    }
+
+    /// This API call creates a new key pair locally in BitGo Express. The key is
+    /// not uploaded; use `add_keychain` with the encrypted private key for that.
+    async fn create_keychain(&self, identifier: &str) -> Result<Keychain> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/keychain/local",
+            url = self.endpoint,
+            coin_type = identifier,
+        );
+        let key = self.post_api(&request_url, &json!({})).await?;
+        Ok(serde_json::from_value(key)?)
+    }
+
+    /// This API call uploads a user or backup key to BitGo.
+    async fn add_keychain(
+        &self,
+        identifier: &str,
+        options: &AddKeychainOptions,
+    ) -> Result<Keychain> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/key",
+            url = self.endpoint,
+            coin_type = identifier,
+        );
+        let key = self.post_api(&request_url, options).await?;
+        Ok(serde_json::from_value(key)?)
+    }
+
+    /// This API call creates the BitGo key of a new wallet on the BitGo service.
+    async fn create_bitgo_keychain(
+        &self,
+        identifier: &str,
+        options: &CreateBitGoKeychainOptions,
+    ) -> Result<Keychain> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/key/bitgo",
+            url = self.endpoint,
+            coin_type = identifier,
+        );
+        let key = self.post_api(&request_url, options).await?;
+        Ok(serde_json::from_value(key)?)
+    }
+
+    /// This API call creates a backup key held by a key recovery service.
+    async fn create_backup_keychain(
+        &self,
+        identifier: &str,
+        options: &CreateBackupKeychainOptions,
+    ) -> Result<Keychain> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/key",
+            url = self.endpoint,
+            coin_type = identifier,
+        );
+        let mut body = serde_json::to_value(options)?;
+        body["source"] = json!("backup");
+        let key = self.post_api(&request_url, &body).await?;
+        Ok(serde_json::from_value(key)?)
+    }
+
+    /// This API call re-encrypts the private key of a keychain with a new passphrase
+    /// through BitGo Express.
+    async fn update_keychain(
+        &self,
+        keychain_id: &str,
+        identifier: &str,
+        options: &UpdateKeychainOptions,
+    ) -> Result<Keychain> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/keychain/{keychain_id}",
+            url = self.endpoint,
+            coin_type = identifier,
+            keychain_id = keychain_id,
+        );
+        let key = self.put_api(&request_url, options).await?;
+        Ok(serde_json::from_value(key)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keychain_debug_redacts_private_key() {
+        let keychain: Keychain = serde_json::from_value(json!({
+            "id": "key",
+            "pub": "xpub661MyMwAqRbcF",
+            "encryptedPrv": "{\"iv\":\"secret\"}",
+            "prv": "xprv9s21ZrQH143K",
+        }))
+        .unwrap();
+
+        let debug = format!("{:?}", keychain);
+
+        assert!(debug.contains("xpub661MyMwAqRbcF"));
+        assert!(!debug.contains("secret"));
+        assert!(!debug.contains("xprv9s21ZrQH143K"));
+    }
+}
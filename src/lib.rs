@@ -1,20 +1,65 @@
+pub mod access_token;
+pub mod address_pool;
+pub mod amount;
+pub mod approval;
 pub mod client;
 pub mod config;
+pub mod dedup;
+pub mod enterprise;
 pub mod error;
+pub mod fee_bump;
+pub mod keychain;
+pub mod mock;
+pub mod policy;
+pub mod policy_reconciler;
 pub mod transfer;
+pub mod unspent;
+pub mod user;
 pub mod wallet;
 pub mod webhook;
-pub mod mock;
 
+mod util;
+
+use crate::access_token::BitGoAccessTokenAPI;
+use crate::approval::BitGoApprovalAPI;
+use crate::enterprise::BitGoEnterpriseAPI;
+use crate::keychain::BitGoKeychainAPI;
+use crate::policy::BitGoPolicyAPI;
 use crate::transfer::BitGoTransferAPI;
+use crate::unspent::BitGoUnspentAPI;
+use crate::user::BitGoUserAPI;
 use crate::wallet::BitGoWalletAPI;
 use crate::webhook::BitGoWebhookAPI;
 pub trait BitGoAPI:
-    BitGoWebhookAPI + BitGoWalletAPI + BitGoTransferAPI + Sync + Send + 'static
+    BitGoWebhookAPI
+    + BitGoWalletAPI
+    + BitGoTransferAPI
+    + BitGoUnspentAPI
+    + BitGoKeychainAPI
+    + BitGoApprovalAPI
+    + BitGoPolicyAPI
+    + BitGoUserAPI
+    + BitGoAccessTokenAPI
+    + BitGoEnterpriseAPI
+    + Sync
+    + Send
+    + 'static
 {
 }
-impl<T: BitGoWebhookAPI + BitGoWalletAPI + BitGoTransferAPI + Sync + Send + 'static> BitGoAPI
-    for T
+impl<
+        T: BitGoWebhookAPI
+            + BitGoWalletAPI
+            + BitGoTransferAPI
+            + BitGoUnspentAPI
+            + BitGoKeychainAPI
+            + BitGoApprovalAPI
+            + BitGoPolicyAPI
+            + BitGoUserAPI
+            + BitGoAccessTokenAPI
+            + BitGoEnterpriseAPI
+            + Sync
+            + Send
+            + 'static,
+    > BitGoAPI for T
 {
 }
-
@@ -1,9 +1,38 @@
-use super::client::BitGoClient;
 use crate::error::Result;
 use async_trait::async_trait;
 use mockall::mock;
 
-use crate::{transfer::BitGoTransferAPI, wallet::BitGoWalletAPI, webhook::BitGoWebhookAPI};
+use crate::access_token::{AccessToken, BitGoAccessTokenAPI, CreateAccessTokenOptions};
+use crate::approval::{
+    BitGoApprovalAPI, PendingApproval, PendingApprovalList, PendingApprovalListOptions,
+    PendingApprovalUpdateOptions,
+};
+use crate::enterprise::{
+    AddEnterpriseUserOptions, BitGoEnterpriseAPI, Enterprise, EnterpriseUsers,
+};
+use crate::keychain::{
+    AddKeychainOptions, BitGoKeychainAPI, CreateBackupKeychainOptions, CreateBitGoKeychainOptions,
+    Keychain, KeychainList, KeychainListOptions, UpdateKeychainOptions,
+};
+use crate::policy::{BitGoPolicyAPI, PolicyRule, PolicyRuleUpdate};
+use crate::transfer::{
+    AccelerateTransactionOptions, BitGoTransferAPI, FeeEstimate, FeeEstimateOptions, SendResult,
+    SweepOptions, Transfer, TransferList, TransferListOptions,
+};
+use crate::unspent::{
+    BitGoUnspentAPI, ConsolidateUnspentsOptions, FanoutUnspentsOptions, UnspentList,
+    UnspentListOptions,
+};
+use crate::user::{BitGoUserAPI, Session, User};
+use crate::wallet::{
+    AcceptShareOptions, AccountConsolidation, AccountConsolidationResult, Address, AddressList,
+    AddressListOptions, BitGoWalletAPI, BuildAccountConsolidationOptions, CoinBalance,
+    CreateAddressOptions, FlushForwarderTokenOptions, GenerateWalletOptions, GenerateWalletResult,
+    MaxSpendable, MaxSpendableOptions, SendAccountConsolidationOptions, ShareWalletOptions,
+    UpdateAddressOptions, UpdateWalletOptions, Wallet, WalletFreeze, WalletList, WalletListOptions,
+    WalletShare, WalletShareList,
+};
+use crate::webhook::{BitGoWebhookAPI, RemoveWebhookOptions, Webhook, WebhookOptions};
 
 mock! {
     pub BitGoClient {
@@ -19,15 +48,26 @@ mock! {
         ) -> Result<serde_json::Value>;
 
         async fn transfer_list(&self, wallet_id: &str, identifier: &str) -> Result<serde_json::Value>;
-        async fn get_fee(
+        async fn list_transfers(
             &self,
+            wallet_id: &str,
             identifier: &str,
-            num_blocks: &i32,
-            recipient: &str,
-            data: &str,
-            amount: &str,
-            hop: bool,
-        ) -> Result<serde_json::Value>;
+            options: &TransferListOptions,
+        ) -> Result<TransferList>;
+        async fn get_transfer_by_sequence_id(
+            &self,
+            wallet_id: &str,
+            identifier: &str,
+            sequence_id: &str,
+        ) -> Result<Transfer>;
+        async fn get_transfer_by_txid(
+            &self,
+            wallet_id: &str,
+            identifier: &str,
+            txid: &str,
+        ) -> Result<Transfer>;
+        async fn get_fee(&self, identifier: &str, options: &FeeEstimateOptions)
+            -> Result<FeeEstimate>;
         async fn change_fee(
             &self,
             identifier: &str,
@@ -35,36 +75,261 @@ mock! {
             tx_id: &str,
             fee: &str,
         ) -> Result<serde_json::Value>;
+        async fn sweep(
+            &self,
+            wallet_id: &str,
+            identifier: &str,
+            options: &SweepOptions,
+        ) -> Result<SendResult>;
+        async fn accelerate_transaction(
+            &self,
+            wallet_id: &str,
+            identifier: &str,
+            options: &AccelerateTransactionOptions,
+        ) -> Result<SendResult>;
+    }
+
+    #[async_trait]
+    impl BitGoApprovalAPI for BitGoClient {
+        async fn list_pending_approvals(
+            &self,
+            options: &PendingApprovalListOptions,
+        ) -> Result<PendingApprovalList>;
+        async fn get_pending_approval(&self, approval_id: &str) -> Result<PendingApproval>;
+        async fn approve_pending_approval(
+            &self,
+            approval_id: &str,
+            identifier: &str,
+            options: &PendingApprovalUpdateOptions,
+        ) -> Result<PendingApproval>;
+        async fn reject_pending_approval(
+            &self,
+            approval_id: &str,
+            identifier: &str,
+            options: &PendingApprovalUpdateOptions,
+        ) -> Result<PendingApproval>;
+    }
+
+    #[async_trait]
+    impl BitGoPolicyAPI for BitGoClient {
+        async fn list_policy_rules(&self, wallet_id: &str, identifier: &str) -> Result<Vec<PolicyRule>>;
+        async fn create_policy_rule(
+            &self,
+            wallet_id: &str,
+            identifier: &str,
+            rule: &PolicyRule,
+        ) -> Result<PolicyRuleUpdate>;
+        async fn update_policy_rule(
+            &self,
+            wallet_id: &str,
+            identifier: &str,
+            rule: &PolicyRule,
+        ) -> Result<PolicyRuleUpdate>;
+        async fn delete_policy_rule(
+            &self,
+            wallet_id: &str,
+            identifier: &str,
+            rule_id: &str,
+        ) -> Result<PolicyRuleUpdate>;
+    }
+
+    #[async_trait]
+    impl BitGoAccessTokenAPI for BitGoClient {
+        async fn list_access_tokens(&self) -> Result<Vec<AccessToken>>;
+        async fn create_access_token(&self, options: &CreateAccessTokenOptions)
+            -> Result<AccessToken>;
+        async fn revoke_access_token(&self, token_id: &str) -> Result<serde_json::Value>;
+    }
+
+    #[async_trait]
+    impl BitGoEnterpriseAPI for BitGoClient {
+        async fn list_enterprises(&self) -> Result<Vec<Enterprise>>;
+        async fn get_enterprise(&self, enterprise_id: &str) -> Result<Enterprise>;
+        async fn list_enterprise_users(&self, enterprise_id: &str) -> Result<EnterpriseUsers>;
+        async fn add_enterprise_user(
+            &self,
+            enterprise_id: &str,
+            options: &AddEnterpriseUserOptions,
+        ) -> Result<serde_json::Value>;
+        async fn remove_enterprise_user(
+            &self,
+            enterprise_id: &str,
+            username: &str,
+        ) -> Result<serde_json::Value>;
+        async fn list_enterprise_wallets(
+            &self,
+            enterprise_id: &str,
+            options: &WalletListOptions,
+        ) -> Result<WalletList>;
+    }
+
+    #[async_trait]
+    impl BitGoUserAPI for BitGoClient {
+        async fn me(&self) -> Result<User>;
+        async fn session(&self) -> Result<Session>;
+        async fn unlock(&self, otp: &str, duration: std::time::Duration) -> Result<Session>;
+        async fn lock(&self) -> Result<serde_json::Value>;
+    }
+
+    #[async_trait]
+    impl BitGoKeychainAPI for BitGoClient {
+        async fn list_keychains(
+            &self,
+            identifier: &str,
+            options: &KeychainListOptions,
+        ) -> Result<KeychainList>;
+        async fn get_keychain(&self, keychain_id: &str, identifier: &str) -> Result<Keychain>;
+        async fn create_keychain(&self, identifier: &str) -> Result<Keychain>;
+        async fn add_keychain(&self, identifier: &str, options: &AddKeychainOptions) -> Result<Keychain>;
+        async fn create_bitgo_keychain(
+            &self,
+            identifier: &str,
+            options: &CreateBitGoKeychainOptions,
+        ) -> Result<Keychain>;
+        async fn create_backup_keychain(
+            &self,
+            identifier: &str,
+            options: &CreateBackupKeychainOptions,
+        ) -> Result<Keychain>;
+        async fn update_keychain(
+            &self,
+            keychain_id: &str,
+            identifier: &str,
+            options: &UpdateKeychainOptions,
+        ) -> Result<Keychain>;
+    }
+
+    #[async_trait]
+    impl BitGoUnspentAPI for BitGoClient {
+        async fn list_unspents(
+            &self,
+            wallet_id: &str,
+            identifier: &str,
+            options: &UnspentListOptions,
+        ) -> Result<UnspentList>;
+        async fn consolidate_unspents(
+            &self,
+            wallet_id: &str,
+            identifier: &str,
+            options: &ConsolidateUnspentsOptions,
+        ) -> Result<Vec<SendResult>>;
+        async fn fanout_unspents(
+            &self,
+            wallet_id: &str,
+            identifier: &str,
+            options: &FanoutUnspentsOptions,
+        ) -> Result<SendResult>;
     }
 
     #[async_trait]
     impl BitGoWalletAPI for BitGoClient {
         async fn generate_wallet(
             &self,
-            name: &str,
             identifier: &str,
-            passphrase: &str,
-        ) -> Result<serde_json::Value>;
+            options: &GenerateWalletOptions,
+        ) -> Result<GenerateWalletResult>;
         async fn generate_enterprise_wallet(
             &self,
-            name: &str,
             identifier: &str,
-            passphrase: &str,
-            enterprise_id:&str,
-        ) -> Result<serde_json::Value>;
+            enterprise_id: &str,
+            options: &GenerateWalletOptions,
+        ) -> Result<GenerateWalletResult>;
         async fn create_address(
             &self,
             wallet_id: &str,
             identifier: &str,
-            forwarde_version: i32,
+            options: &CreateAddressOptions,
         ) -> Result<serde_json::Value>;
-        async fn get_wallet_list(
-            &self
+        async fn get_wallet_list(&self, options: &WalletListOptions) -> Result<WalletList>;
+        async fn get_coin_wallet_list(
+            &self,
+            identifier: &str,
+            options: &WalletListOptions,
+        ) -> Result<WalletList>;
+        async fn list_addresses(
+            &self,
+            wallet_id: &str,
+            identifier: &str,
+            options: &AddressListOptions,
+        ) -> Result<AddressList>;
+        async fn get_address(
+            &self,
+            wallet_id: &str,
+            identifier: &str,
+            address: &str,
+        ) -> Result<Address>;
+        async fn update_address(
+            &self,
+            wallet_id: &str,
+            identifier: &str,
+            address: &str,
+            options: &UpdateAddressOptions,
+        ) -> Result<Address>;
+        async fn get_wallet(&self, wallet_id: &str, identifier: &str) -> Result<Wallet>;
+        async fn update_wallet(
+            &self,
+            wallet_id: &str,
+            identifier: &str,
+            options: &UpdateWalletOptions,
+        ) -> Result<Wallet>;
+        async fn freeze_wallet(
+            &self,
+            wallet_id: &str,
+            identifier: &str,
+            duration: std::time::Duration,
+        ) -> Result<WalletFreeze>;
+        async fn remove_wallet(&self, wallet_id: &str, identifier: &str) -> Result<serde_json::Value>;
+        async fn get_wallet_by_address(&self, address: &str, identifier: &str) -> Result<Wallet>;
+        async fn max_spendable(
+            &self,
+            wallet_id: &str,
+            identifier: &str,
+            options: &MaxSpendableOptions,
+        ) -> Result<MaxSpendable>;
+        async fn get_total_balances(&self) -> Result<Vec<CoinBalance>>;
+        async fn share_wallet(
+            &self,
+            wallet_id: &str,
+            identifier: &str,
+            options: &ShareWalletOptions,
+        ) -> Result<WalletShare>;
+        async fn list_wallet_shares(&self, identifier: &str) -> Result<WalletShareList>;
+        async fn accept_wallet_share(
+            &self,
+            share_id: &str,
+            identifier: &str,
+            options: &AcceptShareOptions,
+        ) -> Result<serde_json::Value>;
+        async fn cancel_wallet_share(&self, share_id: &str, identifier: &str) -> Result<serde_json::Value>;
+        async fn remove_wallet_user(
+            &self,
+            wallet_id: &str,
+            identifier: &str,
+            user_id: &str,
         ) -> Result<serde_json::Value>;
+        async fn build_account_consolidations(
+            &self,
+            wallet_id: &str,
+            identifier: &str,
+            options: &BuildAccountConsolidationOptions,
+        ) -> Result<Vec<AccountConsolidation>>;
+        async fn send_account_consolidations(
+            &self,
+            wallet_id: &str,
+            identifier: &str,
+            options: &SendAccountConsolidationOptions,
+        ) -> Result<AccountConsolidationResult>;
+        async fn flush_forwarder_token(
+            &self,
+            wallet_id: &str,
+            identifier: &str,
+            options: &FlushForwarderTokenOptions,
+        ) -> Result<SendResult>;
     }
 
     #[async_trait]
     impl BitGoWebhookAPI for BitGoClient {
+        #[allow(clippy::too_many_arguments)]
         async fn add_wallet_webhook(
             &self,
             wallet_id: &str,
@@ -105,13 +370,35 @@ mock! {
             webhook_url: &str,
             webhook_id: &str,
         ) -> Result<serde_json::Value>;
+
+        async fn add_enterprise_webhook(
+            &self,
+            enterprise_id: &str,
+            options: &WebhookOptions,
+        ) -> Result<Webhook>;
+        async fn list_enterprise_webhook(&self, enterprise_id: &str) -> Result<Vec<Webhook>>;
+        async fn remove_enterprise_webhook(
+            &self,
+            enterprise_id: &str,
+            options: &RemoveWebhookOptions,
+        ) -> Result<serde_json::Value>;
+        async fn add_user_webhook(&self, options: &WebhookOptions) -> Result<Webhook>;
+        async fn list_user_webhook(&self) -> Result<Vec<Webhook>>;
+        async fn remove_user_webhook(&self, options: &RemoveWebhookOptions) -> Result<serde_json::Value>;
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::client::BitGoClient;
+    use crate::error::Error;
+    use crate::user::{with_unlock, OtpProvider};
+    use crate::wallet::wallet_stream;
+    use crate::webhook::WebhookType;
+    use futures::TryStreamExt;
     use serde_json::json;
+    use std::time::Duration;
 
     #[tokio::test]
     #[ignore = "required bitgo express to be running with tls certificate"]
@@ -171,7 +458,11 @@ mod tests {
 
         if let Ok(cl) = client {
             let res = cl
-                .create_address("60def63ab9390d000630211559c1544d", "tbtc", 0)
+                .create_address(
+                    "60def63ab9390d000630211559c1544d",
+                    "tbtc",
+                    &CreateAddressOptions::default(),
+                )
                 .await
                 .unwrap();
             print!("{:?}", res)
@@ -189,7 +480,10 @@ mod tests {
         );
 
         if let Ok(cl) = client {
-            let res = cl.get_wallet_list().await.unwrap();
+            let res = cl
+                .get_wallet_list(&WalletListOptions::default())
+                .await
+                .unwrap();
             print!("{:?}", res)
         } else {
             todo!()
@@ -202,11 +496,121 @@ mod tests {
             json!({ "address": "2MvrwRYBAuRtPTiZ5MyKg42Ke55W3fZJfZS" }),
         ));
 
-        let v = mock.create_address("any", " any", 0).await.unwrap();
+        let v = mock
+            .create_address("any", " any", &CreateAddressOptions::default())
+            .await
+            .unwrap();
 
         assert_eq!(
             v.get("address").unwrap().to_owned(),
             "2MvrwRYBAuRtPTiZ5MyKg42Ke55W3fZJfZS"
         );
     }
+
+    #[tokio::test]
+    async fn test_mocking_enterprise_webhook() {
+        let mut mock = MockBitGoClient::new();
+        mock.expect_list_enterprise_webhook().returning(|_| {
+            Ok(serde_json::from_value(json!([{
+                "id": "5f1b1c2d",
+                "type": "pendingapproval",
+                "url": "https://example.com/hook",
+                "scope": "enterprise",
+            }]))
+            .unwrap())
+        });
+
+        let v = mock.list_enterprise_webhook("any").await.unwrap();
+
+        assert_eq!(v[0].webhook_type, WebhookType::PendingApproval);
+    }
+
+    #[tokio::test]
+    async fn test_mocking_wallet_stream() {
+        let mut mock = MockBitGoClient::new();
+        mock.expect_get_coin_wallet_list().returning(|_, options| {
+            let page = match options.prev_id.as_deref() {
+                None => json!({
+                    "wallets": [{ "id": "a", "coin": "tbtc" }, { "id": "b", "coin": "tbtc" }],
+                    "nextBatchPrevId": "b",
+                }),
+                Some("b") => json!({ "wallets": [{ "id": "c", "coin": "tbtc" }] }),
+                Some(_) => json!({ "wallets": [] }),
+            };
+            Ok(serde_json::from_value(page).unwrap())
+        });
+
+        let wallets: Vec<Wallet> = wallet_stream(&mock, Some("tbtc"), WalletListOptions::default())
+            .try_collect()
+            .await
+            .unwrap();
+
+        let ids: Vec<&str> = wallets.iter().map(|w| w.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_mocking_wallet_balance() {
+        let mut mock = MockBitGoClient::new();
+        mock.expect_get_wallet().returning(|_, _| {
+            Ok(serde_json::from_value(json!({
+                "id": "wallet",
+                "coin": "teth",
+                "balance": 1.2345678901234568e+22,
+                "balanceString": "12345678901234567890123",
+                "spendableBalanceString": "12345678901234567890000",
+            }))
+            .unwrap())
+        });
+
+        let wallet = mock.get_wallet("wallet", "teth").await.unwrap();
+
+        assert_eq!(
+            wallet.balance.unwrap().to_u128(),
+            Some(12345678901234567890123)
+        );
+        assert_eq!(
+            wallet.spendable_balance.unwrap().as_str(),
+            "12345678901234567890000"
+        );
+    }
+
+    struct FixedOtp;
+
+    #[async_trait]
+    impl OtpProvider for FixedOtp {
+        async fn otp(&self) -> Result<String> {
+            Ok("000000".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mocking_unlock_and_retry() {
+        let mut mock = MockBitGoClient::new();
+        mock.expect_unlock()
+            .withf(|otp, _| otp == "000000")
+            .times(1)
+            .returning(|_, _| Ok(serde_json::from_value(json!({})).unwrap()));
+        let mut seq = mockall::Sequence::new();
+        mock.expect_get_pending_approval()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_| {
+                Err(Error::NeedsUnlock {
+                    msg: "needs unlock".to_string(),
+                })
+            });
+        mock.expect_get_pending_approval()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_| Ok(serde_json::from_value(json!({ "id": "approval" })).unwrap()));
+
+        let approval = with_unlock(&mock, &FixedOtp, Duration::from_secs(60), || {
+            mock.get_pending_approval("approval")
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(approval.id, "approval");
+    }
 }
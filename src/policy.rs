@@ -0,0 +1,274 @@
+use crate::amount::Amount;
+use crate::approval::PendingApproval;
+use crate::client::BitGoClient;
+use crate::error::Result;
+use crate::wallet::{BitGoWalletAPI, WalletAdmin};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// A policy rule of a wallet. Rule types this crate doesn't know about are kept
+/// as [`PolicyCondition::Other`] so they survive a read-modify-write.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyRule {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coin: Option<String>,
+    #[serde(flatten)]
+    pub condition: PolicyCondition,
+    pub action: PolicyAction,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PolicyCondition {
+    Known(PolicyRuleKind),
+    Other {
+        #[serde(rename = "type")]
+        rule_type: String,
+        #[serde(default)]
+        condition: serde_json::Value,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "condition", rename_all = "camelCase")]
+pub enum PolicyRuleKind {
+    VelocityLimit(VelocityLimitCondition),
+    AdvancedWhitelist(WhitelistCondition),
+    Allocation(AllocationCondition),
+    Webhook(WebhookCondition),
+}
+
+/// Limits the amount sent within `time_window` seconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VelocityLimitCondition {
+    #[serde(rename = "amountString")]
+    pub amount: Amount,
+    pub time_window: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub group_tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude_tags: Vec<String>,
+}
+
+/// Restricts destinations to a list of addresses or wallets. Changes are sent as
+/// `add` or `remove`, while BitGo returns the current list in `entries`.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WhitelistCondition {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub add: Option<WhitelistEntry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remove: Option<WhitelistEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entries: Vec<WhitelistEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WhitelistEntry {
+    /// `address` or `walletId`.
+    #[serde(rename = "type")]
+    pub entry_type: String,
+    pub item: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta_data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllocationCondition {
+    #[serde(rename = "amountString", skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_window: Option<u64>,
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Asks the given url whether a transaction may be sent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookCondition {
+    pub url: String,
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyAction {
+    /// `getApproval`, `deny` or `getFinalApproval`.
+    #[serde(rename = "type")]
+    pub action_type: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub user_ids: Vec<String>,
+}
+
+/// Result of changing a rule. Changes to a wallet whose policy is locked are
+/// not applied right away but wait in `pending_approval` for another admin.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PolicyRuleUpdate {
+    pub rules: Vec<PolicyRule>,
+    pub pending_approval: Option<PendingApproval>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PolicyResponse {
+    admin: Option<WalletAdmin>,
+    pending_approval: Option<PendingApproval>,
+}
+
+impl From<PolicyResponse> for PolicyRuleUpdate {
+    fn from(response: PolicyResponse) -> Self {
+        PolicyRuleUpdate {
+            rules: response
+                .admin
+                .and_then(|admin| admin.policy)
+                .map(|policy| policy.rules)
+                .unwrap_or_default(),
+            pending_approval: response.pending_approval,
+        }
+    }
+}
+
+#[async_trait]
+pub trait BitGoPolicyAPI {
+    async fn list_policy_rules(&self, wallet_id: &str, identifier: &str)
+        -> Result<Vec<PolicyRule>>;
+    async fn create_policy_rule(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        rule: &PolicyRule,
+    ) -> Result<PolicyRuleUpdate>;
+    async fn update_policy_rule(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        rule: &PolicyRule,
+    ) -> Result<PolicyRuleUpdate>;
+    async fn delete_policy_rule(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        rule_id: &str,
+    ) -> Result<PolicyRuleUpdate>;
+}
+
+#[async_trait]
+impl BitGoPolicyAPI for BitGoClient {
+    async fn list_policy_rules(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+    ) -> Result<Vec<PolicyRule>> {
+        let wallet = self.get_wallet(wallet_id, identifier).await?;
+        Ok(wallet
+            .admin
+            .and_then(|admin| admin.policy)
+            .map(|policy| policy.rules)
+            .unwrap_or_default())
+    }
+
+    /// This API call adds a rule to the policy of a wallet.
+    async fn create_policy_rule(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        rule: &PolicyRule,
+    ) -> Result<PolicyRuleUpdate> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}/policy/rule",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+        );
+        let response: PolicyResponse =
+            serde_json::from_value(self.post_api(&request_url, rule).await?)?;
+        Ok(response.into())
+    }
+
+    /// This API call replaces the condition and action of an existing rule.
+    async fn update_policy_rule(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        rule: &PolicyRule,
+    ) -> Result<PolicyRuleUpdate> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}/policy/rule",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+        );
+        let response: PolicyResponse =
+            serde_json::from_value(self.put_api(&request_url, rule).await?)?;
+        Ok(response.into())
+    }
+
+    async fn delete_policy_rule(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        rule_id: &str,
+    ) -> Result<PolicyRuleUpdate> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}/policy/rule",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+        );
+        let response: PolicyResponse = serde_json::from_value(
+            self.delete_api(&request_url, &json!({ "id": rule_id }))
+                .await?,
+        )?;
+        Ok(response.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_rule_kinds() {
+        let rules: Vec<PolicyRule> = serde_json::from_value(json!([
+            {
+                "id": "limit",
+                "type": "velocityLimit",
+                "condition": { "amountString": "100000000", "timeWindow": 86400 },
+                "action": { "type": "getApproval", "userIds": ["user"] },
+            },
+            {
+                "id": "unknown",
+                "type": "coinAddressWhitelist",
+                "condition": { "addresses": ["2N"] },
+                "action": { "type": "deny" },
+            },
+        ]))
+        .unwrap();
+
+        match &rules[0].condition {
+            PolicyCondition::Known(PolicyRuleKind::VelocityLimit(limit)) => {
+                assert_eq!(limit.amount.as_str(), "100000000");
+                assert_eq!(limit.time_window, 86400);
+            }
+            other => panic!("unexpected condition {:?}", other),
+        }
+        match &rules[1].condition {
+            PolicyCondition::Other { rule_type, .. } => {
+                assert_eq!(rule_type, "coinAddressWhitelist")
+            }
+            other => panic!("unexpected condition {:?}", other),
+        }
+
+        let json = serde_json::to_value(&rules[0]).unwrap();
+        assert_eq!(json["type"], "velocityLimit");
+        assert_eq!(json["condition"]["amountString"], "100000000");
+    }
+}
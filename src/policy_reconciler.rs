@@ -0,0 +1,531 @@
+use crate::error::{Error, Result};
+use crate::policy::{
+    BitGoPolicyAPI, PolicyCondition, PolicyRule, PolicyRuleKind, WhitelistCondition, WhitelistEntry,
+};
+use crate::wallet::BitGoWalletAPI;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Desired policy rules of a set of wallets, usually loaded from a TOML or JSON
+/// file kept under version control. Rules are matched with the wallet's current
+/// rules by `id`.
+///
+/// ```toml
+/// [[wallets]]
+/// walletId = "5f1b1c2d"
+/// coin = "tbtc"
+///
+/// [[wallets.rules]]
+/// id = "daily-limit"
+/// type = "velocityLimit"
+/// condition = { amountString = "100000000", timeWindow = 86400 }
+/// action = { type = "getApproval" }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PolicySpec {
+    #[serde(default)]
+    pub wallets: Vec<WalletPolicySpec>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletPolicySpec {
+    pub wallet_id: String,
+    pub coin: String,
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+}
+
+impl PolicySpec {
+    pub fn from_toml(spec: &str) -> Result<Self> {
+        toml::from_str(spec).map_err(|err| Error::InvalidPolicySpec {
+            msg: err.to_string(),
+        })
+    }
+
+    pub fn from_json(spec: &str) -> Result<Self> {
+        serde_json::from_str(spec).map_err(|err| Error::InvalidPolicySpec {
+            msg: err.to_string(),
+        })
+    }
+
+    /// Loads a spec from a `.toml` or `.json` file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let spec = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => PolicySpec::from_toml(&spec),
+            Some("json") => PolicySpec::from_json(&spec),
+            _ => Err(Error::InvalidPolicySpec {
+                msg: format!("{} must be a .toml or .json file", path.display()),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "op", content = "rule")]
+pub enum PolicyChange {
+    Create(PolicyRule),
+    Update(PolicyRule),
+    Delete(PolicyRule),
+}
+
+impl PolicyChange {
+    pub fn rule(&self) -> &PolicyRule {
+        match self {
+            PolicyChange::Create(rule)
+            | PolicyChange::Update(rule)
+            | PolicyChange::Delete(rule) => rule,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletPolicyPlan {
+    pub wallet_id: String,
+    pub coin: String,
+    /// Estimated from the wallet having more than one admin: changes to the
+    /// policy of such a wallet wait for another admin to approve them.
+    pub requires_approval: bool,
+    pub changes: Vec<PolicyChange>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PolicyPlan {
+    pub wallets: Vec<WalletPolicyPlan>,
+}
+
+impl PolicyPlan {
+    pub fn is_empty(&self) -> bool {
+        self.wallets.iter().all(|wallet| wallet.changes.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "status", content = "detail")]
+pub enum ChangeStatus {
+    /// Dry run: the change was planned but not sent.
+    Planned,
+    Applied,
+    /// The change waits for admin approval under this pending approval id.
+    PendingApproval(String),
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyChangeReport {
+    pub wallet_id: String,
+    pub change: PolicyChange,
+    pub status: ChangeStatus,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PolicyReport {
+    pub changes: Vec<PolicyChangeReport>,
+}
+
+impl PolicyReport {
+    /// Changes that still need an admin to approve them.
+    pub fn pending_approvals(&self) -> impl Iterator<Item = &PolicyChangeReport> {
+        self.changes
+            .iter()
+            .filter(|change| matches!(change.status, ChangeStatus::PendingApproval(_)))
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &PolicyChangeReport> {
+        self.changes
+            .iter()
+            .filter(|change| matches!(change.status, ChangeStatus::Failed(_)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReconcileOptions {
+    /// Only plan the changes, don't send them.
+    pub dry_run: bool,
+    /// Delete rules that exist on the wallet but not in the spec.
+    pub delete_unmanaged: bool,
+}
+
+/// Brings the policy rules of wallets in line with a [`PolicySpec`].
+pub struct PolicyReconciler<'a, C: ?Sized> {
+    client: &'a C,
+    options: ReconcileOptions,
+}
+
+impl<'a, C> PolicyReconciler<'a, C>
+where
+    C: BitGoWalletAPI + BitGoPolicyAPI + Sync + ?Sized,
+{
+    pub fn new(client: &'a C, options: ReconcileOptions) -> Self {
+        PolicyReconciler { client, options }
+    }
+
+    /// Diffs the spec against the current rules of every wallet in it.
+    pub async fn plan(&self, spec: &PolicySpec) -> Result<PolicyPlan> {
+        let mut plan = PolicyPlan::default();
+        for wallet_spec in spec.wallets.iter() {
+            let wallet = self
+                .client
+                .get_wallet(&wallet_spec.wallet_id, &wallet_spec.coin)
+                .await?;
+            let admins = wallet
+                .users
+                .iter()
+                .filter(|user| user.permissions.iter().any(|p| p == "admin"))
+                .count();
+            let current = wallet
+                .admin
+                .and_then(|admin| admin.policy)
+                .map(|policy| policy.rules)
+                .unwrap_or_default();
+            plan.wallets.push(WalletPolicyPlan {
+                wallet_id: wallet_spec.wallet_id.clone(),
+                coin: wallet_spec.coin.clone(),
+                requires_approval: admins > 1,
+                changes: diff_rules(&current, &wallet_spec.rules, self.options.delete_unmanaged),
+            });
+        }
+        Ok(plan)
+    }
+
+    /// Sends the changes of a plan. A failed change is reported and doesn't stop
+    /// the remaining ones. With `dry_run` every change is reported as planned.
+    pub async fn apply(&self, plan: &PolicyPlan) -> PolicyReport {
+        let mut report = PolicyReport::default();
+        for wallet in plan.wallets.iter() {
+            for change in wallet.changes.iter() {
+                let status = if self.options.dry_run {
+                    ChangeStatus::Planned
+                } else {
+                    self.apply_change(wallet, change).await
+                };
+                log::info!(
+                    "policy rule {} of wallet {}: {:?}",
+                    change.rule().id,
+                    wallet.wallet_id,
+                    status
+                );
+                report.changes.push(PolicyChangeReport {
+                    wallet_id: wallet.wallet_id.clone(),
+                    change: change.clone(),
+                    status,
+                });
+            }
+        }
+        report
+    }
+
+    pub async fn reconcile(&self, spec: &PolicySpec) -> Result<(PolicyPlan, PolicyReport)> {
+        let plan = self.plan(spec).await?;
+        let report = self.apply(&plan).await;
+        Ok((plan, report))
+    }
+
+    async fn apply_change(&self, wallet: &WalletPolicyPlan, change: &PolicyChange) -> ChangeStatus {
+        let result = match change {
+            PolicyChange::Create(rule) => {
+                self.client
+                    .create_policy_rule(&wallet.wallet_id, &wallet.coin, rule)
+                    .await
+            }
+            PolicyChange::Update(rule) => {
+                self.client
+                    .update_policy_rule(&wallet.wallet_id, &wallet.coin, rule)
+                    .await
+            }
+            PolicyChange::Delete(rule) => {
+                self.client
+                    .delete_policy_rule(&wallet.wallet_id, &wallet.coin, &rule.id)
+                    .await
+            }
+        };
+        match result {
+            Ok(update) => match update.pending_approval {
+                Some(approval) => ChangeStatus::PendingApproval(approval.id),
+                None => ChangeStatus::Applied,
+            },
+            Err(err) => ChangeStatus::Failed(err.to_string()),
+        }
+    }
+}
+
+/// Computes the changes turning `current` into `desired`. Whitelists are edited
+/// one entry at a time, so a whitelist diff becomes one change per entry. A new
+/// whitelist is created with its first entry, or empty if it has none.
+pub fn diff_rules(
+    current: &[PolicyRule],
+    desired: &[PolicyRule],
+    delete_unmanaged: bool,
+) -> Vec<PolicyChange> {
+    let mut changes = Vec::new();
+    for rule in desired.iter() {
+        let existing = current.iter().find(|c| c.id == rule.id);
+        match (existing, whitelist_entries(rule)) {
+            (None, Some(entries)) => {
+                let mut entries = entries.iter();
+                changes.push(PolicyChange::Create(whitelist_rule(
+                    rule,
+                    entries.next(),
+                    None,
+                )));
+                changes
+                    .extend(entries.map(|entry| {
+                        PolicyChange::Update(whitelist_rule(rule, Some(entry), None))
+                    }));
+            }
+            (None, None) => changes.push(PolicyChange::Create(rule.clone())),
+            (Some(existing), Some(entries)) => {
+                let planned = changes.len();
+                let current_entries = whitelist_entries(existing).unwrap_or(&[]);
+                for entry in entries
+                    .iter()
+                    .filter(|e| !contains_entry(current_entries, e))
+                {
+                    changes.push(PolicyChange::Update(whitelist_rule(
+                        rule,
+                        Some(entry),
+                        None,
+                    )));
+                }
+                for entry in current_entries
+                    .iter()
+                    .filter(|e| !contains_entry(entries, e))
+                {
+                    changes.push(PolicyChange::Update(whitelist_rule(
+                        rule,
+                        None,
+                        Some(entry),
+                    )));
+                }
+                // Every entry change carries the desired action, so only an
+                // action change without entry changes needs its own update.
+                if changes.len() == planned && existing.action != rule.action {
+                    changes.push(PolicyChange::Update(whitelist_rule(rule, None, None)));
+                }
+            }
+            (Some(existing), None) => {
+                if existing.condition != rule.condition || existing.action != rule.action {
+                    changes.push(PolicyChange::Update(rule.clone()));
+                }
+            }
+        }
+    }
+    if delete_unmanaged {
+        for rule in current.iter() {
+            if !desired.iter().any(|d| d.id == rule.id) {
+                changes.push(PolicyChange::Delete(rule.clone()));
+            }
+        }
+    }
+    changes
+}
+
+fn whitelist_entries(rule: &PolicyRule) -> Option<&[WhitelistEntry]> {
+    match &rule.condition {
+        PolicyCondition::Known(PolicyRuleKind::AdvancedWhitelist(condition)) => {
+            Some(&condition.entries)
+        }
+        _ => None,
+    }
+}
+
+fn contains_entry(entries: &[WhitelistEntry], entry: &WhitelistEntry) -> bool {
+    entries
+        .iter()
+        .any(|e| e.entry_type == entry.entry_type && e.item == entry.item)
+}
+
+fn whitelist_rule(
+    rule: &PolicyRule,
+    add: Option<&WhitelistEntry>,
+    remove: Option<&WhitelistEntry>,
+) -> PolicyRule {
+    PolicyRule {
+        condition: PolicyCondition::Known(PolicyRuleKind::AdvancedWhitelist(WhitelistCondition {
+            add: add.cloned(),
+            remove: remove.cloned(),
+            entries: Vec::new(),
+        })),
+        ..rule.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockBitGoClient;
+    use crate::policy::PolicyRuleUpdate;
+    use serde_json::json;
+
+    const SPEC: &str = r#"
+        [[wallets]]
+        walletId = "wallet"
+        coin = "tbtc"
+
+        [[wallets.rules]]
+        id = "daily-limit"
+        type = "velocityLimit"
+        condition = { amountString = "200000000", timeWindow = 86400 }
+        action = { type = "getApproval" }
+
+        [[wallets.rules]]
+        id = "whitelist"
+        type = "advancedWhitelist"
+        action = { type = "deny" }
+        [wallets.rules.condition]
+        entries = [
+            { type = "address", item = "2N1" },
+            { type = "address", item = "2N3" },
+        ]
+    "#;
+
+    fn client() -> MockBitGoClient {
+        let mut mock = MockBitGoClient::new();
+        mock.expect_get_wallet().returning(|_, _| {
+            Ok(serde_json::from_value(json!({
+                "id": "wallet",
+                "coin": "tbtc",
+                "users": [
+                    { "user": "a", "permissions": ["admin", "spend", "view"] },
+                    { "user": "b", "permissions": ["admin"] },
+                ],
+                "admin": { "policy": { "rules": [
+                    {
+                        "id": "daily-limit",
+                        "type": "velocityLimit",
+                        "condition": { "amountString": "100000000", "timeWindow": 86400 },
+                        "action": { "type": "getApproval" },
+                    },
+                    {
+                        "id": "whitelist",
+                        "type": "advancedWhitelist",
+                        "condition": { "entries": [
+                            { "type": "address", "item": "2N1" },
+                            { "type": "address", "item": "2N2" },
+                        ] },
+                        "action": { "type": "deny" },
+                    },
+                    {
+                        "id": "manual",
+                        "type": "velocityLimit",
+                        "condition": { "amountString": "1", "timeWindow": 60 },
+                        "action": { "type": "deny" },
+                    },
+                ] } },
+            }))
+            .unwrap())
+        });
+        mock
+    }
+
+    #[tokio::test]
+    async fn test_plan() {
+        let spec = PolicySpec::from_toml(SPEC).unwrap();
+        let client = client();
+        let reconciler = PolicyReconciler::new(
+            &client,
+            ReconcileOptions {
+                dry_run: true,
+                delete_unmanaged: true,
+            },
+        );
+
+        let (plan, report) = reconciler.reconcile(&spec).await.unwrap();
+
+        let wallet = &plan.wallets[0];
+        assert!(wallet.requires_approval);
+        let ops: Vec<String> = wallet
+            .changes
+            .iter()
+            .map(|change| serde_json::to_value(change).unwrap()["op"].to_string())
+            .collect();
+        assert_eq!(
+            ops,
+            vec!["\"update\"", "\"update\"", "\"update\"", "\"delete\""]
+        );
+        assert!(report
+            .changes
+            .iter()
+            .all(|change| change.status == ChangeStatus::Planned));
+    }
+
+    #[tokio::test]
+    async fn test_apply_reports_pending_approval() {
+        let spec = PolicySpec::from_toml(SPEC).unwrap();
+        let mut client = client();
+        client.expect_update_policy_rule().returning(|_, _, rule| {
+            let pending_approval = if rule.id == "daily-limit" {
+                Some(serde_json::from_value(json!({ "id": "approval" })).unwrap())
+            } else {
+                None
+            };
+            Ok(PolicyRuleUpdate {
+                rules: Vec::new(),
+                pending_approval,
+            })
+        });
+        let reconciler = PolicyReconciler::new(&client, ReconcileOptions::default());
+
+        let (_, report) = reconciler.reconcile(&spec).await.unwrap();
+
+        let pending: Vec<&str> = report
+            .pending_approvals()
+            .map(|change| change.change.rule().id.as_str())
+            .collect();
+        assert_eq!(pending, vec!["daily-limit"]);
+        assert_eq!(report.failures().count(), 0);
+    }
+
+    fn whitelist(id: &str, action: &str, items: &[&str]) -> PolicyRule {
+        serde_json::from_value(json!({
+            "id": id,
+            "type": "advancedWhitelist",
+            "condition": { "entries": items
+                .iter()
+                .map(|item| json!({ "type": "address", "item": item }))
+                .collect::<Vec<_>>() },
+            "action": { "type": action },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_malformed_spec_is_invalid_in_any_format() {
+        assert!(matches!(
+            PolicySpec::from_toml("wallets = ["),
+            Err(Error::InvalidPolicySpec { .. })
+        ));
+        assert!(matches!(
+            PolicySpec::from_json("{\"wallets\": ["),
+            Err(Error::InvalidPolicySpec { .. })
+        ));
+    }
+
+    #[test]
+    fn test_diff_whitelist_action_and_empty_create() {
+        let current = vec![whitelist("whitelist", "deny", &["2N1"])];
+        let desired = vec![
+            whitelist("whitelist", "getApproval", &["2N1"]),
+            whitelist("empty", "deny", &[]),
+        ];
+
+        let changes = diff_rules(&current, &desired, false);
+
+        assert_eq!(changes.len(), 2);
+        match &changes[0] {
+            PolicyChange::Update(rule) => {
+                assert_eq!(rule.id, "whitelist");
+                assert_eq!(rule.action.action_type, "getApproval");
+            }
+            other => panic!("unexpected change {:?}", other),
+        }
+        match &changes[1] {
+            PolicyChange::Create(rule) => assert_eq!(rule.id, "empty"),
+            other => panic!("unexpected change {:?}", other),
+        }
+    }
+}
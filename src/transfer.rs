@@ -1,8 +1,243 @@
+use crate::amount::Amount;
+use crate::approval::PendingApproval;
 use crate::client::BitGoClient;
 use crate::error::Result;
+use crate::util::redacted;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transfer {
+    pub id: String,
+    pub coin: Option<String>,
+    pub wallet: Option<String>,
+    pub txid: Option<String>,
+    /// Id given by the sender when the transaction was sent.
+    pub sequence_id: Option<String>,
+    /// `send` or `receive`.
+    #[serde(rename = "type")]
+    pub transfer_type: Option<String>,
+    /// `signed`, `unconfirmed`, `confirmed`, `pendingApproval`, `rejected`, ...
+    pub state: Option<String>,
+    #[serde(rename = "valueString")]
+    pub value: Option<Amount>,
+    #[serde(rename = "feeString")]
+    pub fee: Option<Amount>,
+    /// Fee rate in base units per kB.
+    pub fee_rate: Option<u64>,
+    pub v_size: Option<u64>,
+    /// Gas price in wei, for EVM coins.
+    pub gas_price: Option<Amount>,
+    pub confirmations: Option<u32>,
+    pub height: Option<u64>,
+    pub date: Option<DateTime<Utc>>,
+    pub comment: Option<String>,
+    #[serde(default)]
+    pub entries: Vec<TransferEntry>,
+}
+
+impl Transfer {
+    /// Fee rate in base units per kB, as returned by BitGo or derived from the
+    /// fee and virtual size of the transaction.
+    pub fn fee_rate_per_kb(&self) -> Option<u64> {
+        self.fee_rate.or_else(|| {
+            let fee = u64::try_from(self.fee.as_ref()?.to_u128()?).ok()?;
+            let v_size = self.v_size.filter(|v_size| *v_size > 0)?;
+            Some(fee * 1000 / v_size)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferEntry {
+    pub address: Option<String>,
+    pub wallet: Option<String>,
+    #[serde(rename = "valueString")]
+    pub value: Option<Amount>,
+}
+
+/// Options for listing the transfers of a wallet, one page at a time. Pass the
+/// `next_batch_prev_id` of a page as `prev_id` to fetch the following page.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferListOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev_id: Option<String>,
+    /// `send` or `receive`.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub transfer_type: Option<String>,
+    /// Only return transfers in one of these states.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub state: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferList {
+    #[serde(default)]
+    pub transfers: Vec<Transfer>,
+    pub next_batch_prev_id: Option<String>,
+}
+
+/// Result of a transaction built, signed and sent through BitGo Express. When
+/// the send needs approval `pending_approval` is set and nothing was broadcast.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendResult {
+    pub txid: Option<String>,
+    pub tx: Option<String>,
+    pub status: Option<String>,
+    pub transfer: Option<serde_json::Value>,
+    pub pending_approval: Option<PendingApproval>,
+}
+
+/// Options for a fee estimate. Account coins estimate more precisely when the
+/// recipient, data and amount of the transaction are given.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeEstimateOptions {
+    /// Number of blocks within which the transaction should confirm.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_blocks: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipient: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Amount>,
+    /// Estimate for a transaction sent through a hop address.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hop: Option<bool>,
+}
+
+/// Fee estimate of a coin. UTXO coins fill the fee rate fields, EVM coins the
+/// fee and gas fields.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeEstimate {
+    /// Fee rate in base units per kB.
+    pub fee_per_kb: Option<Amount>,
+    /// Fee rate to use for the child of a CPFP acceleration.
+    pub cpfp_fee_per_kb: Option<Amount>,
+    pub num_blocks: Option<u32>,
+    pub confidence: Option<u32>,
+    /// Fee rate per kB by number of blocks to confirm within.
+    #[serde(default)]
+    pub fee_by_block_target: BTreeMap<u32, Amount>,
+    /// Gas price in wei.
+    pub fee_estimate: Option<Amount>,
+    #[serde(alias = "gasLimitEstimate")]
+    pub gas_limit: Option<Amount>,
+    pub eip1559: Option<Eip1559FeeEstimate>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Eip1559FeeEstimate {
+    pub base_fee: Option<Amount>,
+    pub max_fee_per_gas: Option<Amount>,
+    pub max_priority_fee_per_gas: Option<Amount>,
+}
+
+/// Options for sending the whole balance of a wallet to a single address.
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SweepOptions {
+    pub address: String,
+    pub wallet_passphrase: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub otp: Option<String>,
+    /// Fee rate in base units per kB.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_rate: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_fee_rate: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_tx_confirm_target: Option<u32>,
+    /// Sweep what can be sent in one transaction when the wallet holds too many unspents.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_partial_sweep: Option<bool>,
+}
+
+impl std::fmt::Debug for SweepOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SweepOptions")
+            .field("address", &self.address)
+            .field("wallet_passphrase", &"<redacted>")
+            .field("otp", &redacted(&self.otp))
+            .field("fee_rate", &self.fee_rate)
+            .field("max_fee_rate", &self.max_fee_rate)
+            .field("fee_tx_confirm_target", &self.fee_tx_confirm_target)
+            .field("allow_partial_sweep", &self.allow_partial_sweep)
+            .finish()
+    }
+}
+
+/// Options for speeding up a stuck transaction. Set `cpfp_tx_ids` to spend its
+/// change output in a child paying `cpfp_fee_rate` (CPFP), or `rbf_tx_ids` to
+/// replace it with a copy paying `fee_multiplier` times the fee (RBF).
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccelerateTransactionOptions {
+    #[serde(rename = "cpfpTxIds", skip_serializing_if = "Vec::is_empty")]
+    pub cpfp_tx_ids: Vec<String>,
+    /// Fee rate of the parent and child together, in base units per kB.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpfp_fee_rate: Option<u64>,
+    /// Upper bound of the fee paid by the child transaction, in base units.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_fee: Option<u64>,
+    #[serde(rename = "rbfTxIds", skip_serializing_if = "Vec::is_empty")]
+    pub rbf_tx_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_multiplier: Option<f64>,
+    pub wallet_passphrase: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub otp: Option<String>,
+}
+
+impl AccelerateTransactionOptions {
+    pub fn cpfp(tx_id: &str, fee_rate: u64, max_fee: u64, wallet_passphrase: &str) -> Self {
+        AccelerateTransactionOptions {
+            cpfp_tx_ids: vec![tx_id.to_string()],
+            cpfp_fee_rate: Some(fee_rate),
+            max_fee: Some(max_fee),
+            wallet_passphrase: wallet_passphrase.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn rbf(tx_id: &str, fee_multiplier: f64, wallet_passphrase: &str) -> Self {
+        AccelerateTransactionOptions {
+            rbf_tx_ids: vec![tx_id.to_string()],
+            fee_multiplier: Some(fee_multiplier),
+            wallet_passphrase: wallet_passphrase.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+impl std::fmt::Debug for AccelerateTransactionOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AccelerateTransactionOptions")
+            .field("cpfp_tx_ids", &self.cpfp_tx_ids)
+            .field("cpfp_fee_rate", &self.cpfp_fee_rate)
+            .field("max_fee", &self.max_fee)
+            .field("rbf_tx_ids", &self.rbf_tx_ids)
+            .field("fee_multiplier", &self.fee_multiplier)
+            .field("wallet_passphrase", &"<redacted>")
+            .field("otp", &redacted(&self.otp))
+            .finish()
+    }
+}
 
 #[async_trait]
 pub trait BitGoTransferAPI {
@@ -14,15 +249,25 @@ pub trait BitGoTransferAPI {
     ) -> Result<serde_json::Value>;
 
     async fn transfer_list(&self, wallet_id: &str, identifier: &str) -> Result<serde_json::Value>;
-    async fn get_fee(
+    async fn list_transfers(
         &self,
+        wallet_id: &str,
         identifier: &str,
-        num_blocks: &i32,
-        recipient: &str,
-        data: &str,
-        amount: &str,
-        hop: bool,
-    ) -> Result<serde_json::Value>;
+        options: &TransferListOptions,
+    ) -> Result<TransferList>;
+    async fn get_transfer_by_sequence_id(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        sequence_id: &str,
+    ) -> Result<Transfer>;
+    async fn get_transfer_by_txid(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        txid: &str,
+    ) -> Result<Transfer>;
+    async fn get_fee(&self, identifier: &str, options: &FeeEstimateOptions) -> Result<FeeEstimate>;
     async fn change_fee(
         &self,
         identifier: &str,
@@ -30,6 +275,18 @@ pub trait BitGoTransferAPI {
         tx_id: &str,
         fee: &str,
     ) -> Result<serde_json::Value>;
+    async fn sweep(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &SweepOptions,
+    ) -> Result<SendResult>;
+    async fn accelerate_transaction(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &AccelerateTransactionOptions,
+    ) -> Result<SendResult>;
 }
 
 #[async_trait]
@@ -60,31 +317,69 @@ impl BitGoTransferAPI for BitGoClient {
         self.get_api(&request_url, &json!({})).await
     }
 
-    async fn get_fee(
+    /// This API call lists the transfers of a wallet, filtered by `options`.
+    async fn list_transfers(
         &self,
+        wallet_id: &str,
         identifier: &str,
-        num_blocks: &i32,
-        recipient: &str,
-        data: &str,
-        amount: &str,
-        hop: bool,
-    ) -> Result<serde_json::Value> {
+        options: &TransferListOptions,
+    ) -> Result<TransferList> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}/transfer",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+        );
+        let transfers = self.get_api(&request_url, options).await?;
+        Ok(serde_json::from_value(transfers)?)
+    }
+
+    /// This API call looks a transfer up by the `sequenceId` it was sent with,
+    /// so sends can be matched without knowing the BitGo transfer id.
+    async fn get_transfer_by_sequence_id(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        sequence_id: &str,
+    ) -> Result<Transfer> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}/transfer/sequenceId/{sequence_id}",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+            sequence_id = sequence_id,
+        );
+        let transfer = self.get_api(&request_url, &json!({})).await?;
+        Ok(serde_json::from_value(transfer)?)
+    }
+
+    /// This API call looks a transfer up by its transaction hash. BitGo accepts
+    /// the hash in place of the transfer id.
+    async fn get_transfer_by_txid(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        txid: &str,
+    ) -> Result<Transfer> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}/transfer/{txid}",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+            txid = txid,
+        );
+        let transfer = self.get_api(&request_url, &json!({})).await?;
+        Ok(serde_json::from_value(transfer)?)
+    }
+
+    async fn get_fee(&self, identifier: &str, options: &FeeEstimateOptions) -> Result<FeeEstimate> {
         let request_url = format!(
             "{url}/api/v2/{coin_type}/tx/fee",
             url = self.endpoint,
             coin_type = identifier,
         );
-        self.get_api(
-            &request_url,
-            &json!({
-                "numBlocks":num_blocks,
-                "recipient":recipient,
-                "data":data,
-                "amount":amount,
-                "hop":hop,
-            }),
-        )
-        .await
+        let fee = self.get_api(&request_url, options).await?;
+        Ok(serde_json::from_value(fee)?)
     }
 
     async fn change_fee(
@@ -103,4 +398,115 @@ impl BitGoTransferAPI for BitGoClient {
         self.post_api(&request_url, &json!({"txid":tx_id,"fee":fee}))
             .await
     }
+
+    /// This API call sends the full balance of a wallet to the given address
+    /// through BitGo Express. Used when retiring a wallet.
+    async fn sweep(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &SweepOptions,
+    ) -> Result<SendResult> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}/sweep",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+        );
+        let result = self.post_api(&request_url, options).await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    /// This API call speeds up an unconfirmed UTXO transaction through BitGo
+    /// Express, with CPFP or RBF depending on `options`.
+    async fn accelerate_transaction(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &AccelerateTransactionOptions,
+    ) -> Result<SendResult> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}/acceleratetx",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+        );
+        let result = self.post_api(&request_url, options).await?;
+        Ok(serde_json::from_value(result)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_estimate() {
+        let utxo: FeeEstimate = serde_json::from_value(json!({
+            "feePerKb": 15902,
+            "cpfpFeePerKb": 15902,
+            "numBlocks": 2,
+            "confidence": 80,
+            "feeByBlockTarget": { "1": 50536, "2": 15902 },
+        }))
+        .unwrap();
+        assert_eq!(utxo.fee_per_kb, Some(Amount::from(15902u64)));
+        assert_eq!(utxo.fee_by_block_target[&1], Amount::from(50536u64));
+
+        let evm: FeeEstimate = serde_json::from_value(json!({
+            "feeEstimate": "30000000000",
+            "gasLimitEstimate": 21000,
+            "eip1559": {
+                "baseFee": "25000000000",
+                "maxFeePerGas": "52000000000",
+                "maxPriorityFeePerGas": "2000000000",
+            },
+        }))
+        .unwrap();
+        assert_eq!(evm.gas_limit, Some(Amount::from(21000u64)));
+        assert_eq!(
+            evm.eip1559
+                .unwrap()
+                .max_priority_fee_per_gas
+                .unwrap()
+                .as_str(),
+            "2000000000"
+        );
+    }
+
+    #[test]
+    fn test_transfer_fee_rate() {
+        let transfer: Transfer = serde_json::from_value(json!({
+            "id": "transfer",
+            "sequenceId": "payout-1",
+            "type": "send",
+            "state": "signed",
+            "feeString": "2000",
+            "vSize": 200,
+            "date": "2021-08-01T12:00:00.000Z",
+            "entries": [{ "address": "2N", "valueString": "-10000" }],
+        }))
+        .unwrap();
+        assert_eq!(transfer.sequence_id.as_deref(), Some("payout-1"));
+        assert_eq!(transfer.fee_rate_per_kb(), Some(10000));
+        assert_eq!(
+            transfer.entries[0].value.as_ref().unwrap().as_str(),
+            "-10000"
+        );
+    }
+
+    #[test]
+    fn test_accelerate_transaction_options() {
+        let cpfp = serde_json::to_value(AccelerateTransactionOptions::cpfp(
+            "txid", 50000, 100000, "secret",
+        ))
+        .unwrap();
+        assert_eq!(cpfp["cpfpTxIds"], json!(["txid"]));
+        assert_eq!(cpfp["cpfpFeeRate"], 50000);
+        assert!(cpfp.get("rbfTxIds").is_none());
+
+        let rbf = AccelerateTransactionOptions::rbf("txid", 1.5, "secret");
+        assert_eq!(serde_json::to_value(&rbf).unwrap()["feeMultiplier"], 1.5);
+        assert!(!format!("{:?}", rbf).contains("secret"));
+    }
 }
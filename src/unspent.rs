@@ -0,0 +1,272 @@
+use crate::amount::Amount;
+use crate::client::BitGoClient;
+use crate::error::Result;
+use crate::transfer::SendResult;
+use crate::util::redacted;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Unspent {
+    pub id: String,
+    pub address: Option<String>,
+    #[serde(rename = "valueString")]
+    pub value: Option<Amount>,
+    pub block_height: Option<u64>,
+    pub date: Option<String>,
+    pub wallet: Option<String>,
+    pub from_wallet: Option<String>,
+    pub chain: Option<i32>,
+    pub index: Option<i64>,
+    pub redeem_script: Option<String>,
+    pub witness_script: Option<String>,
+    pub is_segwit: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnspentList {
+    pub coin: Option<String>,
+    #[serde(default)]
+    pub unspents: Vec<Unspent>,
+    pub next_batch_prev_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnspentListOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_value: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_value: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_height: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_confirms: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub chains: Vec<i32>,
+}
+
+/// Options for merging many small unspents into fewer large ones. Sent through
+/// BitGo Express, which signs with the user key decrypted by `wallet_passphrase`.
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsolidateUnspentsOptions {
+    pub wallet_passphrase: String,
+    /// Fee rate in base units per kB.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_rate: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_fee_rate: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_fee_percentage: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_tx_confirm_target: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_value: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_value: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_height: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_confirms: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enforce_min_confirms_for_change: Option<bool>,
+    /// Maximum number of unspents to use as inputs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    /// Number of outputs to consolidate into.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_unspents_to_make: Option<u32>,
+    /// Build as many consolidation transactions as needed instead of one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bulk: Option<bool>,
+}
+
+impl std::fmt::Debug for ConsolidateUnspentsOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConsolidateUnspentsOptions")
+            .field("wallet_passphrase", &"<redacted>")
+            .field("fee_rate", &self.fee_rate)
+            .field("max_fee_rate", &self.max_fee_rate)
+            .field("max_fee_percentage", &self.max_fee_percentage)
+            .field("fee_tx_confirm_target", &self.fee_tx_confirm_target)
+            .field("min_value", &self.min_value)
+            .field("max_value", &self.max_value)
+            .field("min_height", &self.min_height)
+            .field("min_confirms", &self.min_confirms)
+            .field(
+                "enforce_min_confirms_for_change",
+                &self.enforce_min_confirms_for_change,
+            )
+            .field("limit", &self.limit)
+            .field("num_unspents_to_make", &self.num_unspents_to_make)
+            .field("bulk", &self.bulk)
+            .finish()
+    }
+}
+
+/// Options for splitting unspents into many outputs so that several sends can
+/// be made in parallel. Sent through BitGo Express.
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FanoutUnspentsOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wallet_passphrase: Option<String>,
+    /// Signs with this user key instead of decrypting it with `wallet_passphrase`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xprv: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_unspents_to_make: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_num_inputs_to_use: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_rate: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_fee_rate: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_fee_percentage: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_tx_confirm_target: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_value: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_value: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_height: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_confirms: Option<u32>,
+}
+
+impl std::fmt::Debug for FanoutUnspentsOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FanoutUnspentsOptions")
+            .field("wallet_passphrase", &redacted(&self.wallet_passphrase))
+            .field("xprv", &redacted(&self.xprv))
+            .field("num_unspents_to_make", &self.num_unspents_to_make)
+            .field("max_num_inputs_to_use", &self.max_num_inputs_to_use)
+            .field("fee_rate", &self.fee_rate)
+            .field("max_fee_rate", &self.max_fee_rate)
+            .field("max_fee_percentage", &self.max_fee_percentage)
+            .field("fee_tx_confirm_target", &self.fee_tx_confirm_target)
+            .field("min_value", &self.min_value)
+            .field("max_value", &self.max_value)
+            .field("min_height", &self.min_height)
+            .field("min_confirms", &self.min_confirms)
+            .finish()
+    }
+}
+
+#[async_trait]
+pub trait BitGoUnspentAPI {
+    async fn list_unspents(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &UnspentListOptions,
+    ) -> Result<UnspentList>;
+    async fn consolidate_unspents(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &ConsolidateUnspentsOptions,
+    ) -> Result<Vec<SendResult>>;
+    async fn fanout_unspents(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &FanoutUnspentsOptions,
+    ) -> Result<SendResult>;
+}
+
+#[async_trait]
+impl BitGoUnspentAPI for BitGoClient {
+    /// This API call lists the unspent outputs of a UTXO wallet, one page at a time.
+    async fn list_unspents(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &UnspentListOptions,
+    ) -> Result<UnspentList> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}/unspents",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+        );
+        let unspents = self.get_api(&request_url, options).await?;
+        Ok(serde_json::from_value(unspents)?)
+    }
+
+    /// This API call builds, signs and sends a transaction merging the selected
+    /// unspents of a wallet back into the wallet. With `bulk` several transactions
+    /// may be sent, one result each.
+    async fn consolidate_unspents(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &ConsolidateUnspentsOptions,
+    ) -> Result<Vec<SendResult>> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}/consolidateunspents",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+        );
+        send_results(self.post_api(&request_url, options).await?)
+    }
+
+    /// This API call builds, signs and sends a transaction splitting the selected
+    /// unspents of a wallet into many outputs of the same wallet.
+    async fn fanout_unspents(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &FanoutUnspentsOptions,
+    ) -> Result<SendResult> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}/fanoutunspents",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+        );
+        let result = self.post_api(&request_url, options).await?;
+        Ok(serde_json::from_value(result)?)
+    }
+}
+
+/// Express answers a bulk consolidation with an array of results and any other
+/// with a single result.
+fn send_results(result: serde_json::Value) -> Result<Vec<SendResult>> {
+    match result {
+        serde_json::Value::Array(results) => results
+            .into_iter()
+            .map(|result| Ok(serde_json::from_value(result)?))
+            .collect(),
+        result => Ok(vec![serde_json::from_value(result)?]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_send_results() {
+        let single = send_results(json!({ "txid": "tx1", "status": "signed" })).unwrap();
+        assert_eq!(single.len(), 1);
+
+        let bulk = send_results(json!([
+            { "txid": "tx1", "status": "signed" },
+            { "txid": "tx2", "status": "signed" },
+        ]))
+        .unwrap();
+        assert_eq!(bulk[1].txid.as_deref(), Some("tx2"));
+    }
+}
@@ -0,0 +1,141 @@
+use crate::client::BitGoClient;
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::future::Future;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    pub id: String,
+    pub username: Option<String>,
+    pub name: Option<UserName>,
+    pub email: Option<UserEmail>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserName {
+    pub full: Option<String>,
+    pub first: Option<String>,
+    pub last: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserEmail {
+    pub email: Option<String>,
+    pub verified: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    pub client: Option<String>,
+    pub user: Option<String>,
+    #[serde(default)]
+    pub scope: Vec<String>,
+    pub created: Option<String>,
+    pub expires: Option<String>,
+    pub origin: Option<String>,
+    /// Set while the session is unlocked.
+    pub unlock: Option<SessionUnlock>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionUnlock {
+    pub time: Option<String>,
+    pub expires: Option<String>,
+    pub tx_count: Option<u32>,
+    pub tx_value: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserResponse {
+    user: User,
+}
+
+#[derive(Debug, Deserialize)]
+struct SessionResponse {
+    session: Session,
+}
+
+/// Source of one-time passwords used to unlock the session, e.g. a TOTP
+/// generator or a prompt to an operator.
+#[async_trait]
+pub trait OtpProvider: Send + Sync {
+    async fn otp(&self) -> Result<String>;
+}
+
+#[async_trait]
+pub trait BitGoUserAPI {
+    async fn me(&self) -> Result<User>;
+    async fn session(&self) -> Result<Session>;
+    async fn unlock(&self, otp: &str, duration: Duration) -> Result<Session>;
+    async fn lock(&self) -> Result<serde_json::Value>;
+}
+
+#[async_trait]
+impl BitGoUserAPI for BitGoClient {
+    /// This API call returns the user owning the access token.
+    async fn me(&self) -> Result<User> {
+        let request_url = format!("{url}/api/v2/user/me", url = self.endpoint);
+        let user: UserResponse =
+            serde_json::from_value(self.get_api(&request_url, &json!({})).await?)?;
+        Ok(user.user)
+    }
+
+    /// This API call returns the current session, including its unlock state.
+    async fn session(&self) -> Result<Session> {
+        let request_url = format!("{url}/api/v2/user/session", url = self.endpoint);
+        let session: SessionResponse =
+            serde_json::from_value(self.get_api(&request_url, &json!({})).await?)?;
+        Ok(session.session)
+    }
+
+    /// This API call unlocks the session for `duration`, allowing sends and
+    /// policy changes that need a second factor.
+    async fn unlock(&self, otp: &str, duration: Duration) -> Result<Session> {
+        let request_url = format!("{url}/api/v2/user/unlock", url = self.endpoint);
+        let session: SessionResponse = serde_json::from_value(
+            self.post_api(
+                &request_url,
+                &json!({ "otp": otp, "duration": duration.as_secs() }),
+            )
+            .await?,
+        )?;
+        Ok(session.session)
+    }
+
+    /// This API call locks the session again before the unlock expires.
+    async fn lock(&self) -> Result<serde_json::Value> {
+        let request_url = format!("{url}/api/v2/user/lock", url = self.endpoint);
+        self.post_api(&request_url, &json!({})).await
+    }
+}
+
+/// Runs `operation`, and if BitGo reports that the session needs unlocking,
+/// unlocks it for `duration` with an OTP from `otp` and runs it once more.
+pub async fn with_unlock<C, F, Fut, T>(
+    client: &C,
+    otp: &dyn OtpProvider,
+    duration: Duration,
+    operation: F,
+) -> Result<T>
+where
+    C: BitGoUserAPI + Sync + ?Sized,
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    match operation().await {
+        Err(Error::NeedsUnlock { msg }) => {
+            log::debug!("unlocking bitgo session: {}", msg);
+            let code = otp.otp().await?;
+            client.unlock(&code, duration).await?;
+            operation().await
+        }
+        result => result,
+    }
+}
@@ -0,0 +1,22 @@
+use crate::error::Result;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Appends `line` to the file at `path` and syncs it to disk on the blocking
+/// thread pool, so the calling runtime thread isn't stalled by the fsync.
+pub(crate) async fn append_line(path: PathBuf, line: String) -> Result<()> {
+    tokio::task::spawn_blocking(move || -> Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        writeln!(file, "{}", line)?;
+        file.sync_data()?;
+        Ok(())
+    })
+    .await
+    .map_err(std::io::Error::other)?
+}
+
+/// Stands in for an optional secret in `Debug` output, showing only whether it is set.
+pub(crate) fn redacted(secret: &Option<String>) -> Option<&'static str> {
+    secret.as_ref().map(|_| "<redacted>")
+}
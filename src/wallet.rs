@@ -1,30 +1,702 @@
+use crate::amount::Amount;
 use crate::client::BitGoClient;
 use crate::error::Result;
+use crate::keychain::Keychain;
+use crate::policy::PolicyRule;
+use crate::transfer::SendResult;
+use crate::util::redacted;
 use async_trait::async_trait;
+use futures::stream::{self, Stream, TryStreamExt};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Wallet {
+    pub id: String,
+    pub coin: String,
+    pub label: Option<String>,
+    pub m: Option<i32>,
+    pub n: Option<i32>,
+    #[serde(default)]
+    pub keys: Vec<String>,
+    pub enterprise: Option<String>,
+    #[serde(rename = "type")]
+    pub wallet_type: Option<String>,
+    pub multisig_type: Option<String>,
+    pub approvals_required: Option<i32>,
+    pub is_cold: Option<bool>,
+    pub deleted: Option<bool>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub users: Vec<WalletUser>,
+    pub admin: Option<WalletAdmin>,
+    /// Balances are only included by `get_wallet` or when listing with `expand_balance`.
+    #[serde(rename = "balanceString")]
+    pub balance: Option<Amount>,
+    #[serde(rename = "confirmedBalanceString")]
+    pub confirmed_balance: Option<Amount>,
+    #[serde(rename = "spendableBalanceString")]
+    pub spendable_balance: Option<Amount>,
+    pub receive_address: Option<serde_json::Value>,
+    /// Fields that only exist for some coins, e.g. the forwarder base address
+    /// on ETH or the root address on XRP.
+    pub coin_specific: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MultisigType {
+    Onchain,
+    Tss,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WalletType {
+    Hot,
+    Cold,
+    Custodial,
+}
+
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateWalletOptions {
+    pub label: String,
+    /// Encrypts the user key; not needed when `user_key` is provided.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passphrase: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enterprise: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multisig_type: Option<MultisigType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wallet_version: Option<i32>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub wallet_type: Option<WalletType>,
+    /// Public key of a user key created outside of BitGo.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_key: Option<String>,
+    /// Public key of a backup key created outside of BitGo.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_xpub: Option<String>,
+    /// Key recovery service holding the backup key, e.g. `keyternal`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_xpub_provider: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disable_transaction_notifications: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_custodial: Option<bool>,
+    /// Encrypts the passphrase so BitGo can help recover the wallet.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passcode_encryption_code: Option<String>,
+}
+
+impl GenerateWalletOptions {
+    pub fn new(label: &str, passphrase: &str) -> Self {
+        GenerateWalletOptions {
+            label: label.to_string(),
+            passphrase: Some(passphrase.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+impl std::fmt::Debug for GenerateWalletOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GenerateWalletOptions")
+            .field("label", &self.label)
+            .field("passphrase", &redacted(&self.passphrase))
+            .field("enterprise", &self.enterprise)
+            .field("multisig_type", &self.multisig_type)
+            .field("wallet_version", &self.wallet_version)
+            .field("wallet_type", &self.wallet_type)
+            .field("user_key", &self.user_key)
+            .field("backup_xpub", &self.backup_xpub)
+            .field("backup_xpub_provider", &self.backup_xpub_provider)
+            .field(
+                "disable_transaction_notifications",
+                &self.disable_transaction_notifications,
+            )
+            .field("is_custodial", &self.is_custodial)
+            .field(
+                "passcode_encryption_code",
+                &redacted(&self.passcode_encryption_code),
+            )
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateWalletResult {
+    pub wallet: Wallet,
+    pub user_keychain: Option<Keychain>,
+    pub backup_keychain: Option<Keychain>,
+    pub bitgo_keychain: Option<Keychain>,
+    /// Set when the backup key was created locally and must be stored by the caller.
+    pub warning: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWalletOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approvals_required: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletFreeze {
+    pub time: Option<String>,
+    pub expires: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaxSpendableOptions {
+    /// Fee rate in base units per kB used to estimate the fee of the sweep.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_rate: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_fee_rate: Option<u64>,
+    /// Ignore unspents smaller than this.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_value: Option<Amount>,
+    /// Ignore unspents larger than this.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_value: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_height: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_confirms: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enforce_min_confirms_for_change: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaxSpendable {
+    pub maximum_spendable: Amount,
+    pub coin: Option<String>,
+}
+
+/// Sum of the balances of all wallets of a coin.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinBalance {
+    pub coin: String,
+    #[serde(rename = "balanceString")]
+    pub balance: Option<Amount>,
+    #[serde(rename = "confirmedBalanceString")]
+    pub confirmed_balance: Option<Amount>,
+    #[serde(rename = "spendableBalanceString")]
+    pub spendable_balance: Option<Amount>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CoinBalanceList {
+    balances: Vec<CoinBalance>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WalletPermission {
+    View,
+    Spend,
+    Admin,
+}
+
+impl WalletPermission {
+    fn as_str(self) -> &'static str {
+        match self {
+            WalletPermission::View => "view",
+            WalletPermission::Spend => "spend",
+            WalletPermission::Admin => "admin",
+        }
+    }
+}
+
+/// BitGo sends and expects share permissions as a single comma separated string.
+mod permission_list {
+    use super::WalletPermission;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        permissions: &[WalletPermission],
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        let permissions: Vec<&str> = permissions.iter().map(|p| p.as_str()).collect();
+        serializer.serialize_str(&permissions.join(","))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Vec<WalletPermission>, D::Error> {
+        let permissions = String::deserialize(deserializer)?;
+        permissions
+            .split(',')
+            .filter(|p| !p.is_empty())
+            .map(|p| serde_json::from_value(serde_json::Value::String(p.trim().to_string())))
+            .collect::<std::result::Result<_, _>>()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Options for sharing a wallet. When `spend` or `admin` is granted Express
+/// decrypts the user key with `wallet_passphrase` and re-encrypts it for the
+/// recipient, so the passphrase is required in that case.
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareWalletOptions {
+    pub email: String,
+    #[serde(with = "permission_list")]
+    pub permissions: Vec<WalletPermission>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wallet_passphrase: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reshare: Option<bool>,
+    /// Share without the encrypted user key, e.g. with a user holding the key out of band.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_keychain: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disable_email: Option<bool>,
+}
+
+impl std::fmt::Debug for ShareWalletOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ShareWalletOptions")
+            .field("email", &self.email)
+            .field("permissions", &self.permissions)
+            .field("wallet_passphrase", &redacted(&self.wallet_passphrase))
+            .field("message", &self.message)
+            .field("reshare", &self.reshare)
+            .field("skip_keychain", &self.skip_keychain)
+            .field("disable_email", &self.disable_email)
+            .finish()
+    }
+}
+
+/// Options for accepting a share. `user_password` decrypts the shared key and
+/// `new_wallet_passphrase` re-encrypts it for the accepting user.
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptShareOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_wallet_passphrase: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub override_encrypted_prv: Option<String>,
+}
+
+impl std::fmt::Debug for AcceptShareOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AcceptShareOptions")
+            .field("user_password", &redacted(&self.user_password))
+            .field(
+                "new_wallet_passphrase",
+                &redacted(&self.new_wallet_passphrase),
+            )
+            .field(
+                "override_encrypted_prv",
+                &redacted(&self.override_encrypted_prv),
+            )
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletShare {
+    pub id: String,
+    pub coin: Option<String>,
+    pub wallet: Option<String>,
+    pub wallet_label: Option<String>,
+    pub from_user: Option<String>,
+    pub to_user: Option<String>,
+    #[serde(with = "permission_list", default)]
+    pub permissions: Vec<WalletPermission>,
+    /// `active`, `accepted`, `canceled`, `rejected` or `pendingapproval`.
+    pub state: Option<String>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WalletShareList {
+    #[serde(default)]
+    pub incoming: Vec<WalletShare>,
+    #[serde(default)]
+    pub outgoing: Vec<WalletShare>,
+}
+
+/// Filters and paging for the wallet listing endpoints. Pass the
+/// `next_batch_prev_id` of a page as `prev_id` to fetch the following page.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletListOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expand_balance: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enterprise: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label_contains: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletList {
+    #[serde(default)]
+    pub wallets: Vec<Wallet>,
+    pub next_batch_prev_id: Option<String>,
+}
+
+/// Script type of a new UTXO address. Each type maps to the external (receive)
+/// chain BitGo uses for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressType {
+    P2sh,
+    P2shP2wsh,
+    P2wsh,
+    P2tr,
+    P2trMusig2,
+}
+
+impl AddressType {
+    pub fn chain(self) -> i32 {
+        match self {
+            AddressType::P2sh => 0,
+            AddressType::P2shP2wsh => 10,
+            AddressType::P2wsh => 20,
+            AddressType::P2tr => 30,
+            AddressType::P2trMusig2 => 40,
+        }
+    }
+}
+
+impl Serialize for AddressType {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_i32(self.chain())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAddressOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain: Option<AddressType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Account coins only: deploy the forwarder with a low fee, at the cost of a slower deployment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub low_priority: Option<bool>,
+    /// Account coins only: gas price in wei for the forwarder deployment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_price: Option<u64>,
+    /// Create the address to receive this token, for coins that need a trustline or ATA.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_token: Option<String>,
+    /// Number of addresses to create in one call.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forwarder_version: Option<i32>,
+    /// EVM coins only: reuse the address already created on another EVM chain
+    /// of the same keyring.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub evm_key_ring_reference_address: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Address {
+    pub id: String,
+    pub address: String,
+    pub chain: Option<i32>,
+    pub index: Option<i64>,
+    pub coin: Option<String>,
+    pub wallet: Option<String>,
+    pub label: Option<String>,
+    pub balance: Option<AddressBalance>,
+    /// Coin specific details, e.g. the forwarder version and whether the
+    /// forwarder contract is pending deployment on account coins.
+    pub coin_specific: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressBalance {
+    pub updated: Option<String>,
+    #[serde(rename = "balanceString")]
+    pub balance: Option<Amount>,
+    #[serde(rename = "confirmedBalanceString")]
+    pub confirmed_balance: Option<Amount>,
+    #[serde(rename = "spendableBalanceString")]
+    pub spendable_balance: Option<Amount>,
+    pub total_received: Option<Amount>,
+    pub total_sent: Option<Amount>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressListOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label_contains: Option<String>,
+    /// Only return addresses on these chains, e.g. `0` and `1` for p2sh.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub chains: Vec<i32>,
+    /// `1` to sort ascending by creation, `-1` for descending.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_balances: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_total_address_count: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressList {
+    pub coin: Option<String>,
+    #[serde(default)]
+    pub addresses: Vec<Address>,
+    pub total_address_count: Option<i64>,
+    pub next_batch_prev_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateAddressOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Account coins only: deploy the forwarder contract of this address now.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deploy_forwarder: Option<bool>,
+    /// Account coins only: flush the balance of this token held by the forwarder.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flush_forwarder_token: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildAccountConsolidationOptions {
+    /// Receive addresses to consolidate; all addresses with a balance when empty.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub consolidate_addresses: Vec<String>,
+}
+
+/// Options for consolidating receive addresses through BitGo Express, which
+/// signs each consolidation with the user key decrypted by `wallet_passphrase`.
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendAccountConsolidationOptions {
+    pub wallet_passphrase: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub consolidate_addresses: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub otp: Option<String>,
+}
+
+impl std::fmt::Debug for SendAccountConsolidationOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SendAccountConsolidationOptions")
+            .field("wallet_passphrase", &"<redacted>")
+            .field("consolidate_addresses", &self.consolidate_addresses)
+            .field("otp", &redacted(&self.otp))
+            .finish()
+    }
+}
+
+/// An unsigned consolidation of one receive address into the base address.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountConsolidation {
+    pub tx_hex: Option<String>,
+    pub tx_request_id: Option<String>,
+    pub consolidate_id: Option<String>,
+    pub fee_info: Option<serde_json::Value>,
+    #[serde(default)]
+    pub recipients: Vec<serde_json::Value>,
+    pub coin_specific: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountConsolidationFailure {
+    pub address: Option<String>,
+    pub message: Option<String>,
+}
+
+/// Outcome of sending consolidations: one entry per receive address, either in
+/// `success` or in `failure`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccountConsolidationResult {
+    #[serde(default)]
+    pub success: Vec<SendResult>,
+    #[serde(default)]
+    pub failure: Vec<AccountConsolidationFailure>,
+}
+
+/// Options for moving tokens stuck in a forwarder to the wallet base address.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlushForwarderTokenOptions {
+    pub forwarder_address: String,
+    pub token_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_price: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletUser {
+    pub user: String,
+    #[serde(default)]
+    pub permissions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletAdmin {
+    pub policy: Option<WalletPolicy>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletPolicy {
+    pub id: Option<String>,
+    pub version: Option<i32>,
+    pub date: Option<String>,
+    pub latest: Option<bool>,
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+}
+
 #[async_trait]
 pub trait BitGoWalletAPI {
     async fn generate_wallet(
         &self,
-        name: &str,
         identifier: &str,
-        passphrase: &str,
-    ) -> Result<serde_json::Value>;
+        options: &GenerateWalletOptions,
+    ) -> Result<GenerateWalletResult>;
     async fn generate_enterprise_wallet(
         &self,
-        name: &str,
         identifier: &str,
-        passphrase: &str,
         enterprise_id: &str,
-    ) -> Result<serde_json::Value>;
+        options: &GenerateWalletOptions,
+    ) -> Result<GenerateWalletResult>;
     async fn create_address(
         &self,
         wallet_id: &str,
         identifier: &str,
-        forwarder_version: i32,
+        options: &CreateAddressOptions,
     ) -> Result<serde_json::Value>;
-    async fn get_wallet_list(&self) -> Result<serde_json::Value>;
+    async fn get_wallet_list(&self, options: &WalletListOptions) -> Result<WalletList>;
+    async fn get_coin_wallet_list(
+        &self,
+        identifier: &str,
+        options: &WalletListOptions,
+    ) -> Result<WalletList>;
+    async fn list_addresses(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &AddressListOptions,
+    ) -> Result<AddressList>;
+    async fn get_address(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        address: &str,
+    ) -> Result<Address>;
+    async fn update_address(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        address: &str,
+        options: &UpdateAddressOptions,
+    ) -> Result<Address>;
+    async fn get_wallet(&self, wallet_id: &str, identifier: &str) -> Result<Wallet>;
+    async fn update_wallet(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &UpdateWalletOptions,
+    ) -> Result<Wallet>;
+    async fn freeze_wallet(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        duration: std::time::Duration,
+    ) -> Result<WalletFreeze>;
+    async fn remove_wallet(&self, wallet_id: &str, identifier: &str) -> Result<serde_json::Value>;
+    async fn get_wallet_by_address(&self, address: &str, identifier: &str) -> Result<Wallet>;
+    async fn max_spendable(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &MaxSpendableOptions,
+    ) -> Result<MaxSpendable>;
+    async fn get_total_balances(&self) -> Result<Vec<CoinBalance>>;
+    async fn share_wallet(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &ShareWalletOptions,
+    ) -> Result<WalletShare>;
+    async fn list_wallet_shares(&self, identifier: &str) -> Result<WalletShareList>;
+    async fn accept_wallet_share(
+        &self,
+        share_id: &str,
+        identifier: &str,
+        options: &AcceptShareOptions,
+    ) -> Result<serde_json::Value>;
+    async fn cancel_wallet_share(
+        &self,
+        share_id: &str,
+        identifier: &str,
+    ) -> Result<serde_json::Value>;
+    async fn remove_wallet_user(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        user_id: &str,
+    ) -> Result<serde_json::Value>;
+    async fn build_account_consolidations(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &BuildAccountConsolidationOptions,
+    ) -> Result<Vec<AccountConsolidation>>;
+    async fn send_account_consolidations(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &SendAccountConsolidationOptions,
+    ) -> Result<AccountConsolidationResult>;
+    async fn flush_forwarder_token(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &FlushForwarderTokenOptions,
+    ) -> Result<SendResult>;
 }
 
 #[async_trait]
@@ -38,23 +710,16 @@ impl BitGoWalletAPI for BitGoClient {
     /// 5.Creates the wallet on BitGo with the 3 public keys above.
     async fn generate_wallet(
         &self,
-        name: &str,
         identifier: &str,
-        passphrase: &str,
-    ) -> Result<serde_json::Value> {
+        options: &GenerateWalletOptions,
+    ) -> Result<GenerateWalletResult> {
         let request_url = format!(
             "{url}/api/v2/{coin_type}/wallet/generate",
             url = self.endpoint,
             coin_type = identifier,
         );
-        self.post_api(
-            &request_url,
-            &json!({
-                "label": name,
-                "passphrase": passphrase,
-            }),
-        )
-        .await
+        let wallet = self.post_api(&request_url, options).await?;
+        Ok(serde_json::from_value(wallet)?)
     }
     /// This API call creates a new wallet. Under the hood, the SDK (or BitGo Express) does the following:
     ///
@@ -65,26 +730,21 @@ impl BitGoWalletAPI for BitGoClient {
     /// 5.Creates the wallet on BitGo with the 3 public keys above.
     async fn generate_enterprise_wallet(
         &self,
-        name: &str,
         identifier: &str,
-        passphrase: &str,
         enterprise_id: &str,
-    ) -> Result<serde_json::Value> {
+        options: &GenerateWalletOptions,
+    ) -> Result<GenerateWalletResult> {
         let request_url = format!(
             "{url}/api/v2/{coin_type}/wallet/generate",
             url = self.endpoint,
             coin_type = identifier,
         );
-
-        self.post_api(
-            &request_url,
-            &json!({
-                "label": name,
-                "passphrase": passphrase,
-                "enterprise":enterprise_id,
-            }),
-        )
-        .await
+        let options = GenerateWalletOptions {
+            enterprise: Some(enterprise_id.to_string()),
+            ..options.clone()
+        };
+        let wallet = self.post_api(&request_url, &options).await?;
+        Ok(serde_json::from_value(wallet)?)
     }
 
     /// This API call is used to create a new receive address for your wallet.
@@ -94,7 +754,7 @@ impl BitGoWalletAPI for BitGoClient {
         &self,
         wallet_id: &str,
         identifier: &str,
-        forwarder_version: i32,
+        options: &CreateAddressOptions,
     ) -> Result<serde_json::Value> {
         let request_url = format!(
             "{url}/api/v2/{coin_type}/wallet/{wallet_id}/address",
@@ -102,17 +762,357 @@ impl BitGoWalletAPI for BitGoClient {
             coin_type = identifier,
             wallet_id = wallet_id,
         );
-        self.post_api(
-            &request_url,
-            &json!({
-                "forwarderVersion":forwarder_version,
-            }),
-        )
-        .await
+        self.post_api(&request_url, options).await
     }
 
-    async fn get_wallet_list(&self) -> Result<serde_json::Value> {
+    /// This API call lists the receive addresses of a wallet, one page at a time.
+    async fn list_addresses(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &AddressListOptions,
+    ) -> Result<AddressList> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}/addresses",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+        );
+        let addresses = self.get_api(&request_url, options).await?;
+        Ok(serde_json::from_value(addresses)?)
+    }
+
+    /// This API call fetches a single address of a wallet by the address itself or by its id.
+    async fn get_address(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        address: &str,
+    ) -> Result<Address> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}/address/{address}",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+            address = address,
+        );
+        let address = self.get_api(&request_url, &json!({})).await?;
+        Ok(serde_json::from_value(address)?)
+    }
+
+    /// This API call updates the label of an address and, on account coins,
+    /// can deploy or flush its forwarder.
+    async fn update_address(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        address: &str,
+        options: &UpdateAddressOptions,
+    ) -> Result<Address> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}/address/{address}",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+            address = address,
+        );
+        let address = self.put_api(&request_url, options).await?;
+        Ok(serde_json::from_value(address)?)
+    }
+
+    /// This API call lists all wallets the user has access to, across all coins.
+    /// Only a single page is returned; see [`wallet_stream`] to walk every page.
+    async fn get_wallet_list(&self, options: &WalletListOptions) -> Result<WalletList> {
         let request_url = format!("{url}/api/v2/wallets/", url = self.endpoint,);
-        self.get_api(&request_url, &json!({})).await
+        let wallets = self.get_api(&request_url, options).await?;
+        Ok(serde_json::from_value(wallets)?)
+    }
+
+    /// This API call lists the wallets of a single coin.
+    async fn get_coin_wallet_list(
+        &self,
+        identifier: &str,
+        options: &WalletListOptions,
+    ) -> Result<WalletList> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet",
+            url = self.endpoint,
+            coin_type = identifier,
+        );
+        let wallets = self.get_api(&request_url, options).await?;
+        Ok(serde_json::from_value(wallets)?)
+    }
+
+    /// This API call retrieves wallet object information by the wallet ID.
+    async fn get_wallet(&self, wallet_id: &str, identifier: &str) -> Result<Wallet> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+        );
+        let wallet = self.get_api(&request_url, &json!({})).await?;
+        Ok(serde_json::from_value(wallet)?)
     }
+
+    /// This API call updates the label, approval count or tags of a wallet.
+    async fn update_wallet(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &UpdateWalletOptions,
+    ) -> Result<Wallet> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+        );
+        let wallet = self.put_api(&request_url, options).await?;
+        Ok(serde_json::from_value(wallet)?)
+    }
+
+    /// This API call freezes a wallet, blocking all sends for the given duration.
+    /// A frozen wallet cannot be unfrozen early.
+    async fn freeze_wallet(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        duration: std::time::Duration,
+    ) -> Result<WalletFreeze> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}/freeze",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+        );
+        let freeze = self
+            .post_api(&request_url, &json!({ "duration": duration.as_secs() }))
+            .await?;
+        Ok(serde_json::from_value(freeze)?)
+    }
+
+    /// This API call removes the wallet from the user's wallet list. The wallet
+    /// itself and its funds remain on chain.
+    async fn remove_wallet(&self, wallet_id: &str, identifier: &str) -> Result<serde_json::Value> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+        );
+        self.delete_api(&request_url, &json!({})).await
+    }
+
+    /// This API call retrieves the wallet owning the given receive address.
+    async fn get_wallet_by_address(&self, address: &str, identifier: &str) -> Result<Wallet> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/address/{address}",
+            url = self.endpoint,
+            coin_type = identifier,
+            address = address,
+        );
+        let wallet = self.get_api(&request_url, &json!({})).await?;
+        Ok(serde_json::from_value(wallet)?)
+    }
+
+    /// This API call returns the maximum amount that can be spent in a single
+    /// transaction, after fees, with the given unspent filters.
+    async fn max_spendable(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &MaxSpendableOptions,
+    ) -> Result<MaxSpendable> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}/maximumSpendable",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+        );
+        let spendable = self.get_api(&request_url, options).await?;
+        Ok(serde_json::from_value(spendable)?)
+    }
+
+    /// This API call returns the total balance per coin across all wallets the user can access.
+    async fn get_total_balances(&self) -> Result<Vec<CoinBalance>> {
+        let request_url = format!("{url}/api/v2/wallet/balances", url = self.endpoint);
+        let list: CoinBalanceList =
+            serde_json::from_value(self.get_api(&request_url, &json!({})).await?)?;
+        Ok(list.balances)
+    }
+
+    /// This API call shares a wallet with another user. Must go through BitGo
+    /// Express when spend permission is granted, as the user key is re-encrypted locally.
+    async fn share_wallet(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &ShareWalletOptions,
+    ) -> Result<WalletShare> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}/share",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+        );
+        let share = self.post_api(&request_url, options).await?;
+        Ok(serde_json::from_value(share)?)
+    }
+
+    /// This API call lists the incoming and outgoing wallet shares of the user.
+    async fn list_wallet_shares(&self, identifier: &str) -> Result<WalletShareList> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/walletshares",
+            url = self.endpoint,
+            coin_type = identifier,
+        );
+        let shares = self.get_api(&request_url, &json!({})).await?;
+        Ok(serde_json::from_value(shares)?)
+    }
+
+    /// This API call accepts an incoming wallet share through BitGo Express.
+    async fn accept_wallet_share(
+        &self,
+        share_id: &str,
+        identifier: &str,
+        options: &AcceptShareOptions,
+    ) -> Result<serde_json::Value> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/walletshare/{share_id}/acceptshare",
+            url = self.endpoint,
+            coin_type = identifier,
+            share_id = share_id,
+        );
+        self.post_api(&request_url, options).await
+    }
+
+    /// This API call cancels an outgoing wallet share or rejects an incoming one.
+    async fn cancel_wallet_share(
+        &self,
+        share_id: &str,
+        identifier: &str,
+    ) -> Result<serde_json::Value> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/walletshare/{share_id}",
+            url = self.endpoint,
+            coin_type = identifier,
+            share_id = share_id,
+        );
+        self.delete_api(&request_url, &json!({})).await
+    }
+
+    /// This API call removes a user from a wallet.
+    async fn remove_wallet_user(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        user_id: &str,
+    ) -> Result<serde_json::Value> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}/user/{user_id}",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+            user_id = user_id,
+        );
+        self.delete_api(&request_url, &json!({})).await
+    }
+
+    /// This API call builds unsigned transactions moving the balances of receive
+    /// addresses of an account coin wallet into its base address.
+    async fn build_account_consolidations(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &BuildAccountConsolidationOptions,
+    ) -> Result<Vec<AccountConsolidation>> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}/consolidateAccount/build",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+        );
+        let consolidations = self.post_api(&request_url, options).await?;
+        Ok(serde_json::from_value(consolidations)?)
+    }
+
+    /// This API call builds, signs and sends the consolidations of an account coin
+    /// wallet through BitGo Express. A failure on one address does not stop the others.
+    async fn send_account_consolidations(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &SendAccountConsolidationOptions,
+    ) -> Result<AccountConsolidationResult> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}/consolidateAccount",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+        );
+        let result = self.post_api(&request_url, options).await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    /// This API call flushes the balance of a token held by a forwarder contract
+    /// to the wallet base address.
+    async fn flush_forwarder_token(
+        &self,
+        wallet_id: &str,
+        identifier: &str,
+        options: &FlushForwarderTokenOptions,
+    ) -> Result<SendResult> {
+        let request_url = format!(
+            "{url}/api/v2/{coin_type}/wallet/{wallet_id}/flushForwarderToken",
+            url = self.endpoint,
+            coin_type = identifier,
+            wallet_id = wallet_id,
+        );
+        let result = self.post_api(&request_url, options).await?;
+        Ok(serde_json::from_value(result)?)
+    }
+}
+
+/// Walks every page of the wallet listing, yielding wallets one by one. With an
+/// `identifier` only the wallets of that coin are listed.
+pub fn wallet_stream<'a, C>(
+    client: &'a C,
+    identifier: Option<&'a str>,
+    options: WalletListOptions,
+) -> impl Stream<Item = Result<Wallet>> + 'a
+where
+    C: BitGoWalletAPI + Sync + ?Sized,
+{
+    stream::try_unfold(Some(options), move |options| {
+        next_wallet_page(client, identifier, options)
+    })
+    .map_ok(|wallets| stream::iter(wallets.into_iter().map(Ok)))
+    .try_flatten()
+}
+
+async fn next_wallet_page<C>(
+    client: &C,
+    identifier: Option<&str>,
+    options: Option<WalletListOptions>,
+) -> Result<Option<(Vec<Wallet>, Option<WalletListOptions>)>>
+where
+    C: BitGoWalletAPI + Sync + ?Sized,
+{
+    let mut options = match options {
+        Some(options) => options,
+        None => return Ok(None),
+    };
+    let page = match identifier {
+        Some(identifier) => client.get_coin_wallet_list(identifier, &options).await?,
+        None => client.get_wallet_list(&options).await?,
+    };
+    let next = match page.next_batch_prev_id {
+        Some(prev_id) if !page.wallets.is_empty() => {
+            options.prev_id = Some(prev_id);
+            Some(options)
+        }
+        _ => None,
+    };
+    Ok(Some((page.wallets, next)))
 }
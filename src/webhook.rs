@@ -1,10 +1,100 @@
 use crate::client::BitGoClient;
 use crate::error::Result;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WebhookType {
+    #[serde(rename = "transfer")]
+    Transfer,
+    #[serde(rename = "transaction")]
+    Transaction,
+    #[serde(rename = "pendingapproval")]
+    PendingApproval,
+    #[serde(rename = "address_confirmation")]
+    AddressConfirmation,
+    #[serde(rename = "block")]
+    Block,
+    #[serde(rename = "wallet_confirmation")]
+    WalletConfirmation,
+    #[serde(rename = "admin")]
+    Admin,
+    #[serde(rename = "accessToken")]
+    AccessToken,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Options for adding a webhook at any scope.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookOptions {
+    #[serde(rename = "type")]
+    pub webhook_type: WebhookType,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_confirmations: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub all_token: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub listen_to_failure_states: Option<bool>,
+}
+
+impl WebhookOptions {
+    pub fn new(webhook_type: WebhookType, url: &str) -> Self {
+        WebhookOptions {
+            webhook_type,
+            url: url.to_string(),
+            label: None,
+            num_confirmations: None,
+            all_token: None,
+            listen_to_failure_states: None,
+        }
+    }
+}
+
+/// Identifies the webhook to remove, either by `id` or by `type` and `url`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveWebhookOptions {
+    #[serde(rename = "type")]
+    pub webhook_type: WebhookType,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub webhook_type: WebhookType,
+    pub url: String,
+    pub label: Option<String>,
+    pub scope: Option<String>,
+    pub coin: Option<String>,
+    pub wallet: Option<String>,
+    pub enterprise: Option<String>,
+    pub created: Option<String>,
+    pub state: Option<String>,
+    pub num_confirmations: Option<i32>,
+    pub all_token: Option<bool>,
+    pub listen_to_failure_states: Option<bool>,
+    pub successive_failed_attempts: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WebhookList {
+    webhooks: Vec<Webhook>,
+}
+
 #[async_trait]
 pub trait BitGoWebhookAPI {
+    #[allow(clippy::too_many_arguments)]
     async fn add_wallet_webhook(
         &self,
         wallet_id: &str,
@@ -50,9 +140,33 @@ pub trait BitGoWebhookAPI {
         webhook_url: &str,
         webhook_id: &str,
     ) -> Result<serde_json::Value>;
+
+    async fn add_enterprise_webhook(
+        &self,
+        enterprise_id: &str,
+        options: &WebhookOptions,
+    ) -> Result<Webhook>;
+
+    async fn list_enterprise_webhook(&self, enterprise_id: &str) -> Result<Vec<Webhook>>;
+
+    async fn remove_enterprise_webhook(
+        &self,
+        enterprise_id: &str,
+        options: &RemoveWebhookOptions,
+    ) -> Result<serde_json::Value>;
+
+    async fn add_user_webhook(&self, options: &WebhookOptions) -> Result<Webhook>;
+
+    async fn list_user_webhook(&self) -> Result<Vec<Webhook>>;
+
+    async fn remove_user_webhook(
+        &self,
+        options: &RemoveWebhookOptions,
+    ) -> Result<serde_json::Value>;
 }
 #[async_trait]
 impl BitGoWebhookAPI for BitGoClient {
+    #[allow(clippy::too_many_arguments)]
     async fn add_wallet_webhook(
         &self,
         wallet_id: &str,
@@ -183,4 +297,67 @@ impl BitGoWebhookAPI for BitGoClient {
         )
         .await
     }
+
+    /// Enterprise webhooks fire for events across every wallet in the enterprise,
+    /// e.g. `pendingapproval` for approvals on any of its wallets.
+    async fn add_enterprise_webhook(
+        &self,
+        enterprise_id: &str,
+        options: &WebhookOptions,
+    ) -> Result<Webhook> {
+        let request_url = format!(
+            "{url}/api/v2/enterprise/{enterprise_id}/webhooks",
+            url = self.endpoint,
+            enterprise_id = enterprise_id,
+        );
+        let webhook = self.post_api(&request_url, options).await?;
+        Ok(serde_json::from_value(webhook)?)
+    }
+
+    async fn list_enterprise_webhook(&self, enterprise_id: &str) -> Result<Vec<Webhook>> {
+        let request_url = format!(
+            "{url}/api/v2/enterprise/{enterprise_id}/webhooks",
+            url = self.endpoint,
+            enterprise_id = enterprise_id,
+        );
+        let list: WebhookList =
+            serde_json::from_value(self.get_api(&request_url, &json!({})).await?)?;
+        Ok(list.webhooks)
+    }
+
+    async fn remove_enterprise_webhook(
+        &self,
+        enterprise_id: &str,
+        options: &RemoveWebhookOptions,
+    ) -> Result<serde_json::Value> {
+        let request_url = format!(
+            "{url}/api/v2/enterprise/{enterprise_id}/webhooks",
+            url = self.endpoint,
+            enterprise_id = enterprise_id,
+        );
+        self.delete_api(&request_url, options).await
+    }
+
+    /// User webhooks are bound to the user owning the access token rather than to a
+    /// wallet or enterprise.
+    async fn add_user_webhook(&self, options: &WebhookOptions) -> Result<Webhook> {
+        let request_url = format!("{url}/api/v2/webhooks", url = self.endpoint);
+        let webhook = self.post_api(&request_url, options).await?;
+        Ok(serde_json::from_value(webhook)?)
+    }
+
+    async fn list_user_webhook(&self) -> Result<Vec<Webhook>> {
+        let request_url = format!("{url}/api/v2/webhooks", url = self.endpoint);
+        let list: WebhookList =
+            serde_json::from_value(self.get_api(&request_url, &json!({})).await?)?;
+        Ok(list.webhooks)
+    }
+
+    async fn remove_user_webhook(
+        &self,
+        options: &RemoveWebhookOptions,
+    ) -> Result<serde_json::Value> {
+        let request_url = format!("{url}/api/v2/webhooks", url = self.endpoint);
+        self.delete_api(&request_url, options).await
+    }
 }